@@ -12,7 +12,7 @@ below = (x, y-1, z)
 bs = c.get_block_state_at(below)
 print "The block below me is", bs.get_id()
 
-wood = c.find_block_ids_within(17, position, 50)[0]
+wood = c.find_blocks_within("minecraft:log", position, 50)[0]
 print "Wood at", wood
 
 print c.find_path_to(position, wood)
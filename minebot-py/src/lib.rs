@@ -3,17 +3,21 @@
 use cpython::*;
 use minebot;
 use minebot::blocks as blocks;
+use minebot::connect::{ChatMode, ConnectOptions, MainHand, Socks5Proxy};
 use minebot::events as events;
 use minebot::events::{EventMatcher};
+use minebot::items as items;
 use minebot::geom::{Distance, Position};
+use minebot::path::{Action, Near, PathOptions};
 use std::cell::RefCell;
+use std::time::Duration;
 
 py_class!(class MinebotClient |py| {
     data client: RefCell<minebot::MinebotClient>;
 
     def listen_for(&self, matchers: &EventMatchers) -> PyResult<Event> {
         let m: &events::EventMatchers = &matchers.matchers(py).borrow();
-        let event = self.client(py).borrow_mut().poll_until_event(m).unwrap();
+        let event = self.client(py).borrow_mut().poll_until_event(m).map_err(|e| client_error(py, e))?;
         Event::create_instance(py, event)
     }
 
@@ -31,12 +35,17 @@ py_class!(class MinebotClient |py| {
     
     def get_my_position(&self) -> PyResult<(Distance, Distance, Distance)> {
         let client = self.client(py).borrow();
-        let position = client.my_position();
+        let position = client.my_position().map_err(|e| client_error(py, e))?;
         let position_tup = (position.x(), position.y(), position.z());
         trace!("My position is {:?}", position_tup);
         Ok(position_tup)
     }
 
+    def get_last_death(&self) -> PyResult<Option<(i32, (Distance, Distance, Distance))>> {
+        Ok(self.client(py).borrow().last_death()
+            .map(|(dimension, pos)| (dimension_id(dimension), (pos.x(), pos.y(), pos.z()))))
+    }
+
     def get_player_names(&self) -> PyResult<Vec<String>> {
         Ok(self.client(py).borrow().player_names().into_iter()
             .map(|p| p.to_string())
@@ -44,7 +53,7 @@ py_class!(class MinebotClient |py| {
     }
 
     def say(&self, message: String) -> PyResult<Option<i32>> {
-        self.client(py).borrow_mut().say(message).unwrap();
+        self.client(py).borrow_mut().say(message).map_err(|e| client_error(py, e))?;
         Ok(None)
     }
 
@@ -64,13 +73,46 @@ py_class!(class MinebotClient |py| {
             .collect())
     }
 
-    def find_path_to(&self, start: (f64, f64, f64), end: (f64, f64, f64)) -> PyResult<Option<Vec<(f64, f64, f64)>>> {
+    def find_blocks_within(&self, query: String, position: (f64, f64, f64), distance: i32) -> PyResult<Vec<(f64, f64, f64)>> {
+        let query = blocks::BlockQuery::parse(&query)
+            .ok_or_else(|| PyErr::new::<exc::ValueError, _>(py, format!("Invalid block query: {}", query)))?;
+        let pos = Position::new(position.0, position.1, position.2).block_position();
+        Ok(self.client(py).borrow().find_blocks_matching(&query, &pos, distance).into_iter()
+            .map(|pos| (pos.x() as f64, pos.y() as f64, pos.z() as f64))
+            .collect())
+    }
+
+    def find_path_to(&self, start: (f64, f64, f64), end: (f64, f64, f64), may_dig: bool = false, place_budget: u32 = 0, max_nodes: Option<usize> = None, partial: bool = false) -> PyResult<Option<Vec<(f64, f64, f64, String)>>> {
         let start_pos = Position::new(start.0, start.1, start.2).block_position();
         let end_pos = Position::new(end.0, end.1, end.2).block_position();
-        Ok(self.client(py).borrow().find_path_to(start_pos, end_pos).map(|r| r.into_iter()
-            .map(|pos| (pos.x() as f64, pos.y() as f64, pos.z() as f64))
+        let options = PathOptions {
+            may_dig,
+            may_place: place_budget > 0,
+            place_budget,
+            max_nodes,
+            partial,
+            ..PathOptions::default()
+        };
+        let goal = Near { pos: end_pos, radius: 2.0 };
+        Ok(self.client(py).borrow().find_path_with(start_pos, &goal, &options).map(|path| path.steps.into_iter()
+            .map(|step| (step.position.x() as f64, step.position.y() as f64, step.position.z() as f64, action_name(&step.action).to_owned()))
             .collect()))
     }
+
+    def travel_to(&self, dest: (f64, f64, f64)) -> PyResult<bool> {
+        let dest_pos = Position::new(dest.0, dest.1, dest.2).block_position();
+        self.client(py).borrow_mut().travel_to(dest_pos).map_err(|e| client_error(py, e))
+    }
+
+    def disconnect(&self) -> PyResult<Option<i32>> {
+        self.client(py).borrow_mut().disconnect().map_err(|e| client_error(py, e))?;
+        Ok(None)
+    }
+
+    def follow(&self, player: String, min_dist: f64, max_dist: f64) -> PyResult<Option<i32>> {
+        self.client(py).borrow_mut().follow(minebot::FollowTarget::Player(player), min_dist, max_dist).map_err(|e| client_error(py, e))?;
+        Ok(None)
+    }
 });
 
 py_class!(class EventMatchers |py| {
@@ -94,6 +136,16 @@ py_class!(class EventMatchers |py| {
         self.matchers(py).borrow_mut().listen(EventMatcher::PlayerListChanged);
         Ok(None)
     }
+
+    def listen_dimension(&self) -> PyResult<Option<i32>> {
+        self.matchers(py).borrow_mut().listen(EventMatcher::DimensionChanged);
+        Ok(None)
+    }
+
+    def listen_death(&self) -> PyResult<Option<i32>> {
+        self.matchers(py).borrow_mut().listen(EventMatcher::Died);
+        Ok(None)
+    }
 });
 
 py_class!(class Event |py| {
@@ -162,6 +214,48 @@ py_class!(class Event |py| {
             _ => Vec::new()
         })
     }
+
+    def is_dimension_change(&self) -> PyResult<bool> {
+        Ok(match self.event(py) {
+            events::Event::DimensionChanged { .. } => true,
+            _ => false
+        })
+    }
+
+    def dimension_new(&self) -> PyResult<Option<i32>> {
+        Ok(match self.event(py) {
+            events::Event::DimensionChanged { new, .. } => Some(dimension_id(*new)),
+            _ => None
+        })
+    }
+
+    def is_death(&self) -> PyResult<bool> {
+        Ok(match self.event(py) {
+            events::Event::Died { .. } => true,
+            _ => false
+        })
+    }
+
+    def death_position(&self) -> PyResult<Option<(Distance, Distance, Distance)>> {
+        Ok(match self.event(py) {
+            events::Event::Died { position: Some(position), .. } => Some((position.x(), position.y(), position.z())),
+            _ => None
+        })
+    }
+
+    def death_cause(&self) -> PyResult<Option<String>> {
+        Ok(match self.event(py) {
+            events::Event::Died { cause, .. } => Some(cause.clone()),
+            _ => None
+        })
+    }
+
+    def death_killer(&self) -> PyResult<Option<i32>> {
+        Ok(match self.event(py) {
+            events::Event::Died { killer, .. } => *killer,
+            _ => None
+        })
+    }
 });
 
 py_class!(class BlockState |py| {
@@ -174,6 +268,10 @@ py_class!(class BlockState |py| {
     def get_meta(&self) -> PyResult<u8> {
         Ok(self.id(py).meta())
     }
+
+    def get_name(&self) -> PyResult<String> {
+        Ok(format!("minecraft:{}", self.id(py).name()))
+    }
 });
 
 py_module_initializer!(libminebot, initlibminebot, PyInit_libminebot, |py, m| {
@@ -183,18 +281,132 @@ py_module_initializer!(libminebot, initlibminebot, PyInit_libminebot, |py, m| {
             .unwrap();
 
     m.add(py, "__doc__", "This module is implemented in Rust.")?;
-    m.add(py, "connect", py_fn!(py, connect(host: String, port: u16, username: String)))?;
+    m.add(py, "connect", py_fn!(py, connect(host: String, port: u16, username: String,
+        locale: Option<String> = None, view_distance: Option<u8> = None, protocol_version: Option<i32> = None,
+        connect_timeout: Option<f64> = None, read_timeout: Option<f64> = None, bind_address: Option<String> = None,
+        proxy: Option<(String, u16)> = None, proxy_auth: Option<(String, String)> = None,
+        silence_timeout: Option<f64> = None, chat_mode: Option<String> = None, chat_colors: Option<bool> = None,
+        skin_parts: Option<u8> = None, main_hand: Option<String> = None)))?;
     m.add(py, "connect_local", py_fn!(py, connect_local(username: String)))?;
+    m.add(py, "block_id", py_fn!(py, block_id(name: String)))?;
+    m.add(py, "item_id", py_fn!(py, item_id(name: String)))?;
     m.add_class::<EventMatchers>(py)?;
     Ok(())
 });
 
-fn connect(py: Python, host: String, port: u16, username: String) -> PyResult<MinebotClient> {
-    println!("Hello!");
-    let client = minebot::MinebotClient::connect(host, port, username).unwrap();
+fn connect(py: Python, host: String, port: u16, username: String,
+    locale: Option<String>, view_distance: Option<u8>, protocol_version: Option<i32>,
+    connect_timeout: Option<f64>, read_timeout: Option<f64>, bind_address: Option<String>,
+    proxy: Option<(String, u16)>, proxy_auth: Option<(String, String)>,
+    silence_timeout: Option<f64>, chat_mode: Option<String>, chat_colors: Option<bool>,
+    skin_parts: Option<u8>, main_hand: Option<String>) -> PyResult<MinebotClient> {
+    let mut options = ConnectOptions::new();
+    if let Some(locale) = locale {
+        options = options.locale(locale);
+    }
+    if let Some(view_distance) = view_distance {
+        options = options.view_distance(view_distance);
+    }
+    if let Some(protocol_version) = protocol_version {
+        options = options.protocol_version(protocol_version);
+    }
+    if let Some(timeout) = connect_timeout {
+        options = options.connect_timeout(duration(py, "connect_timeout", timeout)?);
+    }
+    if let Some(timeout) = read_timeout {
+        options = options.read_timeout(duration(py, "read_timeout", timeout)?);
+    }
+    if let Some(timeout) = silence_timeout {
+        options = options.silence_timeout(duration(py, "silence_timeout", timeout)?);
+    }
+    if let Some(chat_mode) = chat_mode {
+        options = options.chat_mode(match chat_mode.as_ref() {
+            "enabled" => ChatMode::Enabled,
+            "commands_only" => ChatMode::CommandsOnly,
+            "hidden" => ChatMode::Hidden,
+            _ => return Err(PyErr::new::<exc::ValueError, _>(py, format!("Invalid chat mode: {}", chat_mode)))
+        });
+    }
+    if let Some(chat_colors) = chat_colors {
+        options = options.chat_colors(chat_colors);
+    }
+    if let Some(skin_parts) = skin_parts {
+        options = options.skin_parts(skin_parts);
+    }
+    if let Some(main_hand) = main_hand {
+        options = options.main_hand(match main_hand.as_ref() {
+            "left" => MainHand::Left,
+            "right" => MainHand::Right,
+            _ => return Err(PyErr::new::<exc::ValueError, _>(py, format!("Invalid main hand: {}", main_hand)))
+        });
+    }
+    if let Some(address) = bind_address {
+        let address = address.parse()
+            .map_err(|_| PyErr::new::<exc::ValueError, _>(py, format!("Invalid bind address: {}", address)))?;
+        options = options.bind_address(address);
+    }
+    if let Some((proxy_host, proxy_port)) = proxy {
+        let mut socks = Socks5Proxy::new(proxy_host, proxy_port);
+        if let Some((username, password)) = proxy_auth {
+            socks = socks.auth(username, password);
+        }
+        options = options.proxy(socks);
+    }
+
+    let client = minebot::MinebotClient::connect_with(host, port, username, &options).map_err(|e| client_error(py, e))?;
     MinebotClient::create_instance(py, RefCell::new(client))
 }
 
 fn connect_local(py: Python, username: String) -> PyResult<MinebotClient> {
-    connect(py, "localhost".to_owned(), 25565, username)
+    connect(py, "localhost".to_owned(), 25565, username, None, None, None, None, None, None, None, None, None,
+        None, None, None, None)
+}
+
+// Timeouts are given in seconds, sockets reject zero and negative ones
+fn duration(py: Python, name: &str, seconds: f64) -> PyResult<Duration> {
+    if !(seconds > 0.0) || !seconds.is_finite() {
+        return Err(PyErr::new::<exc::ValueError, _>(py, format!("{} must be a positive number of seconds", name)));
+    }
+    Ok(Duration::from_millis((seconds * 1000.0).ceil() as u64))
+}
+
+
+fn block_id(_py: Python, name: String) -> PyResult<Option<u16>> {
+    Ok(blocks::block_by_name(&name).map(|b| b.id()))
 }
+
+fn item_id(_py: Python, name: String) -> PyResult<Option<u16>> {
+    Ok(items::item_by_name(&name).map(|i| i.id()))
+}
+
+fn client_error(py: Python, err: minebot::Error) -> PyErr {
+    match err {
+        minebot::Error::Io(ref e) => PyErr::new::<exc::IOError, _>(py, e.to_string()),
+        minebot::Error::Timeout => PyErr::new::<exc::IOError, _>(py, err.to_string()),
+        minebot::Error::Disconnected(_) => PyErr::new::<exc::IOError, _>(py, err.to_string()),
+        _ => PyErr::new::<exc::RuntimeError, _>(py, err.to_string())
+    }
+}
+
+fn action_name(action: &Action) -> &'static str {
+    match *action {
+        Action::Walk => "walk",
+        Action::Jump => "jump",
+        Action::Fall => "fall",
+        Action::Parkour => "parkour",
+        Action::Climb => "climb",
+        Action::Swim => "swim",
+        Action::Dig(_) => "dig",
+        Action::Place(_) => "place",
+        Action::Pillar => "pillar",
+        Action::Explore => "explore"
+    }
+}
+
+fn dimension_id(dimension: minebot::DimensionId) -> i32 {
+    match dimension {
+        minebot::DimensionId::Nether => -1,
+        minebot::DimensionId::Overworld => 0,
+        minebot::DimensionId::End => 1
+    }
+}
\ No newline at end of file
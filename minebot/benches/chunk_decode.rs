@@ -0,0 +1,140 @@
+#[macro_use] extern crate criterion;
+
+use bytes::{Buf, BufMut, Bytes, IntoBuf};
+use criterion::Criterion;
+use minebot::chunk::Chunk;
+use std::fs;
+use std::path::Path;
+
+// ChunkData payloads recorded from a 1.12.2 server with
+// `cargo run --example capture_chunks`. Each file holds the dimension id as
+// one signed byte and the primary bitmask as a big endian u16, followed by
+// the packet's data field.
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/fixtures");
+
+struct Fixture {
+    name: String,
+    has_skylight: bool,
+    bitmask: u16,
+    data: Bytes
+}
+
+fn load_fixtures() -> Vec<Fixture> {
+    let mut paths: Vec<_> = match fs::read_dir(FIXTURES) {
+        Ok(entries) => entries.filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "chunk"))
+            .collect(),
+        Err(_) => Vec::new()
+    };
+    paths.sort();
+    assert!(!paths.is_empty(), "No chunk fixtures in {}, record some with \
+        `cargo run --example capture_chunks -- <host> <port> <username>` against a 1.12.2 server", FIXTURES);
+    paths.iter().map(|path| load_fixture(path)).collect()
+}
+
+fn load_fixture(path: &Path) -> Fixture {
+    let mut buf = Bytes::from(fs::read(path).unwrap()).into_buf();
+    let dimension = buf.get_i8();
+    let bitmask = buf.get_u16_be();
+    let data = buf.collect();
+    Fixture {
+        name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+        has_skylight: dimension == 0,
+        bitmask,
+        data
+    }
+}
+
+// Builds the payload of a 1.12 ChunkData packet for a column with the given
+// number of sections, using the same palette-plus-packed-longs encoding the
+// server sends (entries may straddle long boundaries). Palettes of more than
+// 256 entries are sent as global state ids instead.
+fn encode_column(sections: usize, palette: &[u16], block_at: impl Fn(usize, usize) -> usize) -> Bytes {
+    let global = palette.len() > 256;
+    let bits = if global { 13 } else { (palette.len().next_power_of_two().trailing_zeros() as usize).max(4) };
+    let longs = 4096 * bits / 64;
+    let mut buf = Vec::new();
+    for section in 0..sections {
+        buf.put_u8(bits as u8);
+        if global {
+            put_varint(&mut buf, 0);
+        } else {
+            put_varint(&mut buf, palette.len() as u32);
+            for state in palette {
+                put_varint(&mut buf, *state as u32);
+            }
+        }
+        put_varint(&mut buf, longs as u32);
+
+        let mut packed = vec![0u64; longs];
+        for idx in 0..4096 {
+            let val = block_at(section, idx);
+            let val = if global { palette[val] } else { val as u16 } as u64;
+            let bit = idx * bits;
+            packed[bit / 64] |= val << (bit % 64);
+            if bit % 64 + bits > 64 {
+                packed[bit / 64 + 1] |= val >> (64 - bit % 64);
+            }
+        }
+        for long in packed {
+            buf.put_u64_be(long);
+        }
+
+        buf.extend(std::iter::repeat_n(0u8, 2048));
+        buf.extend(std::iter::repeat_n(0xFFu8, 2048));
+    }
+    Bytes::from(buf)
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut val: u32) {
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if val == 0 {
+            buf.put_u8(byte);
+            return;
+        }
+        buf.put_u8(byte | 0x80);
+    }
+}
+
+fn decode_column(data: &Bytes, bitmask: u16, has_skylight: bool) -> Chunk {
+    let mut chunk = Chunk::new();
+    let mut buf = data.clone().into_buf();
+    for section_y in 0..16 {
+        if bitmask & (1 << section_y) != 0 {
+            chunk.load_section(section_y, &mut buf, has_skylight).unwrap();
+        }
+    }
+    chunk
+}
+
+fn bench_decode(c: &mut Criterion) {
+    for fixture in load_fixtures() {
+        let Fixture { name, has_skylight, bitmask, data } = fixture;
+        c.bench_function(&format!("decode {}", name), move |b| b.iter(|| decode_column(&data, bitmask, has_skylight)));
+    }
+
+    let plains = encode_column(4, &[0, 1 << 4, 3 << 4, 2 << 4, 16 << 4, 7 << 4], |section, idx| {
+        let y = section * 16 + (idx >> 8);
+        match y {
+            0 => 5,
+            60..=62 => 2,
+            63 => 3,
+            _ if idx % 97 == 0 => 4,
+            _ => 1
+        }
+    });
+    c.bench_function("decode synthetic plains column", move |b| b.iter(|| decode_column(&plains, 0x000F, true)));
+
+    let palette: Vec<u16> = (1..200).map(|id| id << 4).collect();
+    let mixed = encode_column(16, &palette, |section, idx| (section * 31 + idx * 7) % 199);
+    c.bench_function("decode synthetic mixed column", move |b| b.iter(|| decode_column(&mixed, 0xFFFF, true)));
+
+    let palette: Vec<u16> = (1..400).map(|state| ((state / 16 + 1) << 4) | (state % 16)).collect();
+    let direct = encode_column(16, &palette, |section, idx| (section * 31 + idx * 7) % 399);
+    c.bench_function("decode synthetic direct palette column", move |b| b.iter(|| decode_column(&direct, 0xFFFF, true)));
+}
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+use minebot::blocks::BlockState;
+use minebot::chunk::Chunk;
+use minebot::geom::LocalAddr;
+use std::mem::size_of;
+
+const RESERVED_BLOCKS: usize = 80 * 16 * 16;
+
+// Size of the flat layout chunks used before section storage: one u16 block
+// state and one u8 each of light and skylight per block up to the highest
+// written address, plus an untouched damage vector, each reserving
+// RESERVED_BLOCKS entries up front.
+fn flat_layout_size(highest_addr: usize) -> usize {
+    let len = (highest_addr + 1).max(RESERVED_BLOCKS);
+    len * size_of::<u16>() + 2 * len * size_of::<u8>() + RESERVED_BLOCKS * size_of::<u8>()
+}
+
+fn fill(chunk: &mut Chunk, height: u16, block_at: impl Fn(u8, u8, u8) -> BlockState) {
+    for y in 0..height {
+        for z in 0..16 {
+            for x in 0..16 {
+                let addr = LocalAddr::new(x, y as u8, z);
+                chunk.set_block_state(addr, block_at(x, y as u8, z));
+                chunk.set_light_level(addr, 0);
+                chunk.set_skylight_level(addr, if y > 63 { 15 } else { 0 });
+            }
+        }
+    }
+}
+
+fn report(name: &str, chunk: &Chunk, height: u16) {
+    let flat = flat_layout_size(height as usize * 256 - 1);
+    let sections = chunk.storage_heap_size();
+    println!("{:<10} flat: {:>8} bytes   sections: {:>8} bytes ({:>5.1}%)",
+        name, flat, sections, 100.0 * sections as f64 / flat as f64);
+}
+
+fn main() {
+    let mut plains = Chunk::new();
+    fill(&mut plains, 64, |x, y, z| match y {
+        0 => BlockState(7 << 4),
+        1..=59 if (x as u16 * 7 + y as u16 * 13 + z as u16 * 5).is_multiple_of(97) => BlockState(16 << 4),
+        1..=59 => BlockState(1 << 4),
+        60..=62 => BlockState(3 << 4),
+        _ => BlockState(2 << 4)
+    });
+    report("plains", &plains, 64);
+
+    let mut ocean = Chunk::new();
+    fill(&mut ocean, 64, |_, y, _| match y {
+        0 => BlockState(7 << 4),
+        1..=40 => BlockState(1 << 4),
+        41..=44 => BlockState(13 << 4),
+        _ => BlockState(9 << 4)
+    });
+    report("ocean", &ocean, 64);
+
+    let mut mixed = Chunk::new();
+    fill(&mut mixed, 128, |x, y, z| {
+        let id = (x as u16 * 31 + y as u16 * 17 + z as u16 * 7) % 40 + 1;
+        BlockState(id << 4 | (y as u16 & 0x03))
+    });
+    report("mixed", &mixed, 128);
+
+    let view_distance = 8;
+    let chunks = (2 * view_distance + 1) * (2 * view_distance + 1);
+    println!("{} plains chunks at view distance {}: flat {} KiB, sections {} KiB",
+        chunks, view_distance,
+        chunks * flat_layout_size(64 * 256 - 1) / 1024,
+        chunks * plains.storage_heap_size() / 1024);
+}
@@ -0,0 +1,48 @@
+// Records the ChunkData packets a server sends into benches/fixtures, in the
+// format the chunk_decode benchmark reads.
+//
+//     cargo run --example capture_chunks -- localhost 25565 recorder 30
+//
+// Captures for this many seconds, move the bot between dimensions in the
+// meantime to record nether or end chunks.
+
+use bytes::BufMut;
+use minebot::{DimensionId, MinebotClient, Result};
+use packets::ServerPacket;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 4 {
+        eprintln!("usage: {} <host> <port> <username> [seconds]", args[0]);
+        return Ok(());
+    }
+    let port = args[2].parse().expect("invalid port");
+    let seconds = args.get(4).map_or(30, |s| s.parse().expect("invalid number of seconds"));
+
+    let out_dir = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/benches/fixtures"));
+    fs::create_dir_all(&out_dir)?;
+
+    let mut client = MinebotClient::connect(args[1].clone(), port, args[3].clone())?;
+    let until = Instant::now() + Duration::from_secs(seconds);
+    while Instant::now() < until {
+        if let Some(ServerPacket::ChunkData { chunk_x, chunk_z, full_chunk: true, primary_bitmask, data }) = client.poll()? {
+            let (name, id) = match client.dimension() {
+                DimensionId::Nether => ("nether", -1),
+                DimensionId::Overworld => ("overworld", 0),
+                DimensionId::End => ("end", 1)
+            };
+            let mut buf = Vec::with_capacity(data.len() + 3);
+            buf.put_i8(id);
+            buf.put_u16_be(primary_bitmask as u16);
+            buf.extend_from_slice(&data);
+            let path = out_dir.join(format!("{}_{}_{}.chunk", name, chunk_x, chunk_z));
+            fs::write(&path, buf)?;
+            println!("Recorded {}", path.display());
+        }
+    }
+    client.disconnect()
+}
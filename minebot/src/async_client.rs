@@ -0,0 +1,288 @@
+use crate::{acknowledgement, disconnect_reason, position_packet, responses, Error, Result};
+use crate::blocks::{BlockQuery, BlockState};
+use crate::clock::Clock;
+use crate::connect::ConnectOptions;
+use crate::events::{Event, EventMatchers};
+use crate::gamestate::GameState;
+use crate::geom::{BlockPosition, Position};
+use crate::path::{Goal, Path, PathOptions};
+use crate::watchdog::Watchdog;
+use futures::{stream, SinkExt, Stream, StreamExt};
+use nbt::framed::PacketCodec;
+use packets::*;
+use std::io::ErrorKind;
+use std::net::Shutdown;
+use tokio::net::TcpStream;
+use tokio::task;
+use tokio::time::{self, Instant};
+use tokio_util::codec::{Framed, FramedParts};
+use uuid::Uuid;
+
+// The same client as MinebotClient, driven by a tokio runtime instead of
+// blocking reads, for running many bots on a few threads
+pub struct AsyncMinebotClient {
+    framed: Framed<TcpStream, PacketCodec<ServerPacket>>,
+    gamestate: GameState,
+    clock: Clock,
+    watchdog: Watchdog
+}
+
+impl AsyncMinebotClient {
+    pub async fn connect(host: String, port: u16, username: String) -> Result<Self> {
+        Self::connect_with(host, port, username, &ConnectOptions::default()).await
+    }
+
+    pub async fn connect_with(host: String, port: u16, username: String, options: &ConnectOptions) -> Result<Self> {
+        info!("Connecting to {}:{}...", host, port);
+        // Proxies, binding and timeouts are handled by the blocking connect
+        let sock = {
+            let options = options.clone();
+            let host = host.clone();
+            task::spawn_blocking(move || options.open(&host, port)).await
+                .map_err(|e| Error::InvalidState(format!("connect task failed: {}", e)))??
+        };
+        let mut framed = Framed::new(TcpStream::from_std(sock)?, PacketCodec::<ServerLoginPacket>::new());
+
+        let packet = options.handshake(&host, port);
+        trace!("Sending: {:?}", packet);
+        framed.send(packet).await?;
+
+        let packet = ClientLoginPacket::LoginStart {
+            name: username.clone()
+        };
+        trace!("Sending: {:?}", packet);
+        framed.send(packet).await?;
+
+        let packet = framed.next().await
+            .ok_or_else(|| Error::Disconnected("connection closed during login".to_owned()))??;
+        trace!("Received: {:?}", packet);
+        let uuid = match packet {
+            ServerLoginPacket::LoginSuccess { uuid, .. } => {
+                uuid
+            }
+            ServerLoginPacket::Disconnect { reason } => {
+                return Err(Error::Disconnected(disconnect_reason(&reason)));
+            }
+        };
+        info!("Successfully connected, player id is {}", uuid);
+
+        let uuid = Uuid::parse_str(uuid.as_ref())
+            .map_err(|_| Error::InvalidState(format!("server sent an invalid player id {}", uuid)))?;
+
+        // Same connection, from now on it carries play packets
+        let login = framed.into_parts();
+        let mut play = FramedParts::new::<ClientPacket>(login.io, PacketCodec::<ServerPacket>::new());
+        play.read_buf = login.read_buf;
+        play.write_buf = login.write_buf;
+
+        let mut res = AsyncMinebotClient {
+            framed: Framed::from_parts(play),
+            gamestate: GameState::new(uuid, username),
+            clock: Clock::default(),
+            watchdog: options.watchdog()
+        };
+
+        res.poll_until(|packet| matches!(packet, ServerPacket::PlayerAbilities { .. })).await?;
+
+        res.send(options.client_settings()).await?;
+
+        res.poll_until(|packet| matches!(packet, ServerPacket::KeepAlive { .. })).await?;
+
+        Ok(res)
+    }
+
+    pub async fn send(&mut self, packet: ClientPacket) -> Result<()> {
+        trace!("Sending: {:?}", packet);
+        self.framed.send(packet).await?;
+        Ok(())
+    }
+
+    // Waits at most until the end of the current tick
+    pub async fn poll(&mut self) -> Result<Option<ServerPacket>> {
+        let packet = self.receive().await?;
+        if let Some(got_packet) = packet.as_ref() {
+            self.handle(got_packet).await?;
+        }
+        Ok(packet)
+    }
+
+    pub async fn poll_until<F>(&mut self, pred: F) -> Result<ServerPacket>
+        where F: Fn(&ServerPacket) -> bool {
+        loop {
+            if let Some(got_packet) = self.poll().await? {
+                if pred(&got_packet) {
+                    return Ok(got_packet);
+                }
+            }
+        }
+    }
+
+    pub async fn poll_until_event(&mut self, matchers: &EventMatchers) -> Result<Event> {
+        loop {
+            if let Some(got_packet) = self.receive().await? {
+                let event = matchers.match_packet(&got_packet, &self.gamestate);
+                self.handle(&got_packet).await?;
+                if let Some(evt) = event {
+                    return Ok(evt);
+                }
+            }
+        }
+    }
+
+    // Ends after the first connection error
+    pub fn events<'a>(&'a mut self, matchers: &'a EventMatchers) -> impl Stream<Item = Result<Event>> + 'a {
+        stream::unfold(Some(self), move |client| async move {
+            let client = client?;
+            match client.poll_until_event(matchers).await {
+                Err(err) if err.is_connection_error() => Some((Err(err), None)),
+                res => Some((res, Some(client)))
+            }
+        })
+    }
+
+    // Keeps handling packets until the current tick is over
+    pub async fn tick(&mut self) -> Result<()> {
+        self.clock.advance();
+        let end = self.clock.current_tick_end();
+        while std::time::Instant::now() < end {
+            self.poll().await?;
+        }
+        Ok(())
+    }
+
+    pub fn current_tick(&self) -> i64 {
+        self.clock.current_tick()
+    }
+
+    async fn handle(&mut self, packet: &ServerPacket) -> Result<()> {
+        if let Some(ack) = acknowledgement(packet) {
+            self.send(ack).await?;
+        }
+        self.gamestate.handle_packet(packet)?;
+        self.clock.handle_packet(packet);
+        for response in responses(packet, &self.gamestate)? {
+            self.send(response).await?;
+        }
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<Option<ServerPacket>> {
+        self.clock.advance();
+        let deadline = Instant::from_std(self.clock.current_tick_end());
+        let packet = match time::timeout_at(deadline, self.framed.next()).await {
+            Err(_) => None,
+            Ok(None) => return Err(Error::Disconnected("connection closed".to_owned())),
+            Ok(Some(packet)) => Some(packet?)
+        };
+        if let Some(ref got_packet) = packet {
+            self.watchdog.handle_packet(got_packet);
+            match &got_packet {
+                ServerPacket::ChunkData { chunk_x, chunk_z, .. } => {
+                    trace!("Received: ChunkData {{ chunk_x: {}, chunk_z: {}, ... }}", chunk_x, chunk_z);
+                }
+                p => trace!("Received: {:?}", p)
+            }
+        }
+        if self.watchdog.expired() {
+            return Err(Error::Timeout);
+        }
+        Ok(packet)
+    }
+
+    pub fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting");
+        match self.framed.get_ref().shutdown(Shutdown::Both) {
+            Err(ref e) if e.kind() == ErrorKind::NotConnected => Ok(()),
+            res => Ok(res?)
+        }
+    }
+
+    pub fn health(&self) -> f32 {
+        self.gamestate.health()
+    }
+
+    pub fn food(&self) -> f32 {
+        self.gamestate.food()
+    }
+
+    pub fn my_position(&self) -> Result<&Position> {
+        self.gamestate.my_position()
+    }
+
+    pub async fn set_my_position(&mut self, position: Position) -> Result<()> {
+        self.gamestate.set_my_position(position)?;
+        let packet = position_packet(&self.gamestate)?;
+        self.send(packet).await
+    }
+
+    pub fn dimension(&self) -> DimensionId {
+        self.gamestate.dimension()
+    }
+
+    pub fn last_death(&self) -> Option<(DimensionId, Position)> {
+        self.gamestate.last_death()
+    }
+
+    pub async fn say<M: Into<String>>(&mut self, msg: M) -> Result<()> {
+        self.send(ClientPacket::ChatMessage { message: msg.into() }).await
+    }
+
+    pub fn block_state_at(&self, position: &BlockPosition) -> Option<BlockState> {
+        self.gamestate.block_state_at(position)
+    }
+
+    pub fn find_blocks_matching(&self, query: &BlockQuery, position: &BlockPosition, distance: i32) -> Vec<BlockPosition> {
+        self.gamestate.find_blocks_within(|bs| query.matches(bs), position, distance)
+    }
+
+    pub fn find_path_with(&self, start: BlockPosition, goal: &dyn Goal, options: &PathOptions) -> Option<Path> {
+        self.gamestate.find_path_with(start, goal, options)
+    }
+
+    pub fn player_names(&self) -> Vec<&str> {
+        self.gamestate.player_names()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventMatcher;
+    use crate::fake_server::{FakeServer, CLIENT_KEEP_ALIVE, CLIENT_POSITION_AND_LOOK};
+    use std::thread;
+    use std::time::Duration;
+    use tokio::runtime;
+
+    #[test]
+    fn connects_handles_ticks_and_events() {
+        let server = FakeServer::bind();
+        let port = server.port();
+        let server = thread::spawn(move || server.accept());
+        let mut runtime = runtime::Builder::new().basic_scheduler().enable_all().build().unwrap();
+        runtime.block_on(async {
+            let options = ConnectOptions::new().silence_timeout(Duration::from_secs(5));
+            let mut client = AsyncMinebotClient::connect_with("127.0.0.1".to_owned(), port, "bot".to_owned(), &options).await.unwrap();
+            let mut conn = server.join().unwrap();
+
+            conn.join_game(7);
+            conn.teleport(10.5, 70.0, -3.5, 1);
+            conn.keep_alive(42);
+            let tick = client.current_tick();
+            client.tick().await.unwrap();
+            // Only counted once the next tick starts
+            client.tick().await.unwrap();
+            assert!(client.current_tick() > tick);
+            assert_eq!(*client.my_position().unwrap(), Position::new(10.5, 70.0, -3.5));
+            conn.expect(CLIENT_POSITION_AND_LOOK);
+            assert_eq!(conn.expect(CLIENT_KEEP_ALIVE), 42i64.to_be_bytes());
+
+            let mut matchers = EventMatchers::default();
+            matchers.listen(EventMatcher::ChatMessage);
+            conn.chat("alex", "hi");
+            let event = Box::pin(client.events(&matchers)).next().await;
+            match event {
+                Some(Ok(Event::ChatMessage { player, message })) => assert_eq!((player.as_ref(), message.as_ref()), ("alex", "hi")),
+                event => panic!("expected ChatMessage, got {:?}", event)
+            }
+        });
+    }
+}
@@ -1,4 +1,12 @@
-#[derive(Debug, Clone, Copy)]
+mod query;
+mod v340;
+
+pub use query::BlockQuery;
+
+use crate::geom::Aabb;
+use crate::PROTOCOL_VERSION;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct BlockState(pub u16);
 
 impl BlockState {
@@ -10,10 +18,487 @@ impl BlockState {
         (self.0 & 0x0F) as u8
     }
 
+    pub fn info(&self) -> &'static BlockInfo {
+        registry(PROTOCOL_VERSION)
+            .and_then(|r| r.get(self.id()))
+            .unwrap_or(&UNKNOWN)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.info().name()
+    }
+
+    pub fn hardness(&self) -> f32 {
+        self.info().hardness()
+    }
+
+    pub fn best_tool(&self) -> Option<Tool> {
+        self.info().best_tool()
+    }
+
+    pub fn harvest_tier(&self) -> Option<ToolTier> {
+        self.info().harvest_tier()
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.info().is_transparent()
+    }
+
+    pub fn is_liquid(&self) -> bool {
+        self.info().is_liquid()
+    }
+
+    // Lava is the only liquid that hurts
+    pub fn is_water(&self) -> bool {
+        self.is_liquid() && !self.is_dangerous()
+    }
+
+    pub fn is_lava(&self) -> bool {
+        self.is_liquid() && self.is_dangerous()
+    }
+
+    pub fn is_climbable(&self) -> bool {
+        self.info().is_climbable()
+    }
+
+    pub fn is_dangerous(&self) -> bool {
+        self.info().is_dangerous()
+    }
+
+    pub fn is_solid(&self) -> bool {
+        self.info().shape() == Shape::Full
+    }
+
+    // Relative to the block's lowest corner
+    pub fn collision_box(&self) -> Option<Aabb> {
+        self.info().shape().collision_box(self.meta())
+    }
+
+    // An open door swings against the side of its block, leaving room to
+    // walk through
     pub fn is_passable(&self) -> bool {
+        let is_open_door = self.info().shape() == Shape::Door && self.meta() & 0x04 != 0;
+        (self.collision_box().is_none() || is_open_door) && !self.is_dangerous()
+    }
+
+    // Fences and walls stick out into the block above, standing on them
+    // would need a jump of more than one block
+    pub fn can_stand_on(&self) -> bool {
+        self.collision_box().is_some_and(|collision_box| collision_box.max().y() <= 1.0) && !self.is_dangerous()
+    }
+
+    pub fn can_harvest(&self, tool: Option<(Tool, Option<ToolTier>)>) -> bool {
+        match self.harvest_tier() {
+            None => true,
+            Some(required) => tool.is_some_and(|(tool, tier)| {
+                Some(tool) == self.best_tool() &&
+                    tier.is_some_and(|tier| tier.harvest_level() >= required.harvest_level())
+            })
+        }
+    }
+
+    pub fn dig_ticks(&self, tool: Option<(Tool, Option<ToolTier>)>) -> Option<u32> {
+        let hardness = self.hardness();
+        if hardness < 0.0 || self.is_liquid() {
+            return None;
+        }
+        if hardness == 0.0 {
+            return Some(0);
+        }
+
+        let speed = match tool {
+            Some((Tool::Shears, _)) if self.id() == 18 || self.id() == 161 || self.id() == 30 => 15.0,
+            Some((Tool::Sword, _)) if self.id() == 30 => 15.0,
+            Some((tool, tier)) if Some(tool) == self.best_tool() => match tool {
+                Tool::Shears => 5.0,
+                Tool::Sword => 1.5,
+                _ => tier.map_or(1.0, |t| t.speed())
+            },
+            _ => 1.0
+        };
+        let divisor = if self.can_harvest(tool) { 30.0 } else { 100.0 };
+        let damage = speed / hardness / divisor;
+        if damage >= 1.0 {
+            Some(0)
+        } else {
+            Some((1.0 / damage).ceil() as u32)
+        }
+    }
+
+    pub fn is_falling(&self) -> bool {
         let id = self.id();
-        id == 0 ||
-            id == 31 ||
-            id == 32
+        id == 12 || id == 13 || id == 252
+    }
+
+    pub fn properties(&self) -> Vec<(&'static str, &'static str)> {
+        let meta = self.meta() as usize;
+        match self.id() {
+            1 => vec![("variant", index(&STONE_VARIANTS, meta))],
+            3 => vec![("variant", index(&DIRT_VARIANTS, meta))],
+            5 | 6 => vec![("variant", index(&WOOD_VARIANTS, meta & 0x07))],
+            8..=11 => vec![("level", NUMBERS[meta])],
+            12 => vec![("variant", index(&SAND_VARIANTS, meta))],
+            17 | 18 => {
+                let mut props = vec![("variant", WOOD_VARIANTS[meta & 0x03])];
+                if self.id() == 17 {
+                    props.push(("axis", AXES[meta >> 2]));
+                }
+                props
+            }
+            161 | 162 => {
+                let mut props = vec![("variant", WOOD_VARIANTS[4 + (meta & 0x01)])];
+                if self.id() == 162 {
+                    props.push(("axis", AXES[meta >> 2]));
+                }
+                props
+            }
+            31 => vec![("type", index(&TALLGRASS_VARIANTS, meta))],
+            35 | 95 | 159 | 160 | 171 | 251 | 252 => vec![("color", COLORS[meta])],
+            38 => vec![("type", index(&FLOWER_VARIANTS, meta))],
+            43 | 44 => {
+                let mut props = vec![("variant", STONE_SLAB_VARIANTS[meta & 0x07])];
+                if self.id() == 44 {
+                    props.push(("half", slab_half(meta)));
+                }
+                props
+            }
+            125 | 126 => {
+                let mut props = vec![("variant", index(&WOOD_VARIANTS, meta & 0x07))];
+                if self.id() == 126 {
+                    props.push(("half", slab_half(meta)));
+                }
+                props
+            }
+            59 | 83 | 104 | 105 | 141 | 142 => vec![("age", NUMBERS[meta & 0x07])],
+            81 => vec![("age", NUMBERS[meta])],
+            115 | 207 => vec![("age", NUMBERS[meta & 0x03])],
+            60 => vec![("moisture", NUMBERS[meta & 0x07])],
+            64 | 71 | 193 | 194 | 195 | 196 | 197 => if meta & 0x08 != 0 {
+                vec![("half", "upper")]
+            } else {
+                vec![("facing", DOOR_FACINGS[meta & 0x03]), ("half", "lower"), ("open", bool_str(meta & 0x04 != 0))]
+            },
+            78 => vec![("layers", NUMBERS[(meta & 0x07) + 1])],
+            96 | 167 => vec![("half", slab_half(meta)), ("open", bool_str(meta & 0x04 != 0))],
+            107 | 183 | 184 | 185 | 186 | 187 => vec![("open", bool_str(meta & 0x04 != 0))],
+            175 => if meta & 0x08 != 0 {
+                vec![("half", "upper")]
+            } else {
+                vec![("variant", index(&DOUBLE_PLANT_VARIANTS, meta & 0x07)), ("half", "lower")]
+            },
+            _ => Vec::new()
+        }
+    }
+
+    pub fn property(&self, name: &str) -> Option<&'static str> {
+        self.properties().into_iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+}
+
+static NUMBERS: [&str; 16] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15"];
+static AXES: [&str; 4] = ["y", "x", "z", "none"];
+static DOOR_FACINGS: [&str; 4] = ["east", "south", "west", "north"];
+static COLORS: [&str; 16] = ["white", "orange", "magenta", "light_blue", "yellow", "lime", "pink", "gray",
+    "silver", "cyan", "purple", "blue", "brown", "green", "red", "black"];
+static STONE_VARIANTS: [&str; 7] = ["stone", "granite", "smooth_granite", "diorite", "smooth_diorite",
+    "andesite", "smooth_andesite"];
+static DIRT_VARIANTS: [&str; 3] = ["dirt", "coarse_dirt", "podzol"];
+static SAND_VARIANTS: [&str; 2] = ["sand", "red_sand"];
+static WOOD_VARIANTS: [&str; 6] = ["oak", "spruce", "birch", "jungle", "acacia", "dark_oak"];
+static TALLGRASS_VARIANTS: [&str; 3] = ["dead_bush", "tall_grass", "fern"];
+static FLOWER_VARIANTS: [&str; 9] = ["poppy", "blue_orchid", "allium", "houstonia", "red_tulip", "orange_tulip",
+    "white_tulip", "pink_tulip", "oxeye_daisy"];
+static STONE_SLAB_VARIANTS: [&str; 8] = ["stone", "sandstone", "wood_old", "cobblestone", "brick", "stone_brick",
+    "nether_brick", "quartz"];
+static DOUBLE_PLANT_VARIANTS: [&str; 6] = ["sunflower", "syringa", "double_grass", "double_fern", "double_rose",
+    "paeonia"];
+
+fn index(values: &[&'static str], idx: usize) -> &'static str {
+    values.get(idx).cloned().unwrap_or("unknown")
+}
+
+fn slab_half(meta: usize) -> &'static str {
+    if meta & 0x08 != 0 {
+        "top"
+    } else {
+        "bottom"
+    }
+}
+
+fn bool_str(val: bool) -> &'static str {
+    if val {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Pickaxe,
+    Axe,
+    Shovel,
+    Shears,
+    Sword,
+    Hoe
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolTier {
+    Wood,
+    Stone,
+    Iron,
+    Diamond,
+    Gold
+}
+
+impl ToolTier {
+    pub fn harvest_level(self) -> u8 {
+        match self {
+            ToolTier::Wood | ToolTier::Gold => 0,
+            ToolTier::Stone => 1,
+            ToolTier::Iron => 2,
+            ToolTier::Diamond => 3
+        }
+    }
+
+    pub fn speed(self) -> f32 {
+        match self {
+            ToolTier::Wood => 2.0,
+            ToolTier::Stone => 4.0,
+            ToolTier::Iron => 6.0,
+            ToolTier::Diamond => 8.0,
+            ToolTier::Gold => 12.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Empty,
+    Full,
+    Height(f64),
+    Slab,
+    Layers,
+    Stairs,
+    Fence,
+    Gate,
+    Door,
+    Trapdoor
+}
+
+impl Shape {
+    pub fn collision_box(self, meta: u8) -> Option<Aabb> {
+        match self {
+            Shape::Empty => None,
+            Shape::Full => Some(Aabb::from_parts(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)),
+            Shape::Height(height) => Some(Aabb::from_parts(0.0, 0.0, 0.0, 1.0, height, 1.0)),
+            Shape::Slab => if meta & 0x08 != 0 {
+                Some(Aabb::from_parts(0.0, 0.5, 0.0, 1.0, 1.0, 1.0))
+            } else {
+                Some(Aabb::from_parts(0.0, 0.0, 0.0, 1.0, 0.5, 1.0))
+            },
+            Shape::Layers => {
+                let layers = meta & 0x07;
+                if layers == 0 {
+                    None
+                } else {
+                    Some(Aabb::from_parts(0.0, 0.0, 0.0, 1.0, layers as f64 / 8.0, 1.0))
+                }
+            }
+            // Both steps together, which is what a player steps up onto
+            Shape::Stairs => Some(Aabb::from_parts(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)),
+            Shape::Fence => Some(Aabb::from_parts(0.375, 0.0, 0.375, 0.625, 1.5, 0.625)),
+            Shape::Gate => if meta & 0x04 != 0 {
+                None
+            } else {
+                Some(Aabb::from_parts(0.0, 0.0, 0.375, 1.0, 1.5, 0.625))
+            },
+            // The upper half of a door carries neither facing nor the open
+            // bit, so only the lower half ever blocks movement. Its hinge is
+            // in the upper half, open doors are taken to be hinged left.
+            Shape::Door => if meta & 0x08 != 0 {
+                None
+            } else {
+                let facing = if meta & 0x04 != 0 { (meta + 1) & 0x03 } else { meta & 0x03 };
+                Some(match facing {
+                    0 => Aabb::from_parts(0.0, 0.0, 0.0, 0.1875, 1.0, 1.0),
+                    1 => Aabb::from_parts(0.0, 0.0, 0.0, 1.0, 1.0, 0.1875),
+                    2 => Aabb::from_parts(0.8125, 0.0, 0.0, 1.0, 1.0, 1.0),
+                    _ => Aabb::from_parts(0.0, 0.0, 0.8125, 1.0, 1.0, 1.0)
+                })
+            },
+            Shape::Trapdoor => if meta & 0x04 != 0 {
+                None
+            } else if meta & 0x08 != 0 {
+                Some(Aabb::from_parts(0.0, 0.8125, 0.0, 1.0, 1.0, 1.0))
+            } else {
+                Some(Aabb::from_parts(0.0, 0.0, 0.0, 1.0, 0.1875, 1.0))
+            }
+        }
+    }
+}
+
+const TRANSPARENT: u8 = 0x01;
+const LIQUID: u8 = 0x02;
+const CLIMBABLE: u8 = 0x04;
+const DANGEROUS: u8 = 0x08;
+
+#[derive(Debug)]
+pub struct BlockInfo {
+    id: u16,
+    name: &'static str,
+    hardness: f32,
+    best_tool: Option<Tool>,
+    harvest_tier: Option<ToolTier>,
+    flags: u8,
+    shape: Shape
+}
+
+const fn block(id: u16, name: &'static str, hardness: f32, best_tool: Option<Tool>,
+    harvest_tier: Option<ToolTier>, flags: u8, shape: Shape) -> BlockInfo {
+    BlockInfo { id, name, hardness, best_tool, harvest_tier, flags, shape }
+}
+
+static UNKNOWN: BlockInfo = block(0xFFFF, "unknown", -1.0, None, None, 0, Shape::Full);
+
+impl BlockInfo {
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn hardness(&self) -> f32 {
+        self.hardness
+    }
+
+    pub fn is_breakable(&self) -> bool {
+        self.hardness >= 0.0
+    }
+
+    pub fn best_tool(&self) -> Option<Tool> {
+        self.best_tool
+    }
+
+    pub fn harvest_tier(&self) -> Option<ToolTier> {
+        self.harvest_tier
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.flags & TRANSPARENT != 0
+    }
+
+    pub fn is_liquid(&self) -> bool {
+        self.flags & LIQUID != 0
+    }
+
+    pub fn is_climbable(&self) -> bool {
+        self.flags & CLIMBABLE != 0
+    }
+
+    pub fn is_dangerous(&self) -> bool {
+        self.flags & DANGEROUS != 0
+    }
+
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+}
+
+pub struct BlockRegistry {
+    protocol_version: i32,
+    blocks: &'static [BlockInfo]
+}
+
+static REGISTRIES: [BlockRegistry; 1] = [
+    BlockRegistry { protocol_version: 340, blocks: &v340::BLOCKS }
+];
+
+pub fn registry(protocol_version: i32) -> Option<&'static BlockRegistry> {
+    REGISTRIES.iter().find(|r| r.protocol_version == protocol_version)
+}
+
+pub fn block_by_name(name: &str) -> Option<&'static BlockInfo> {
+    registry(PROTOCOL_VERSION)?.by_name(name)
+}
+
+impl BlockRegistry {
+    pub fn protocol_version(&self) -> i32 {
+        self.protocol_version
+    }
+
+    pub fn get(&self, id: u16) -> Option<&'static BlockInfo> {
+        self.blocks.binary_search_by_key(&id, |b| b.id)
+            .ok()
+            .map(|idx| &self.blocks[idx])
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&'static BlockInfo> {
+        let name = name.trim_start_matches("minecraft:");
+        self.blocks.iter().find(|b| b.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static BlockInfo> {
+        self.blocks.iter()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(name: &str, meta: u8) -> BlockState {
+        BlockState((block_by_name(name).unwrap().id() << 4) | meta as u16)
+    }
+
+    fn bounds(bs: BlockState) -> Option<[f64; 6]> {
+        bs.collision_box().map(|b| [b.min().x(), b.min().y(), b.min().z(), b.max().x(), b.max().y(), b.max().z()])
+    }
+
+    #[test]
+    fn collision_boxes() {
+        let cases = [
+            (state("air", 0), None),
+            (state("stone", 0), Some([0.0, 0.0, 0.0, 1.0, 1.0, 1.0])),
+            (state("stone_slab", 0), Some([0.0, 0.0, 0.0, 1.0, 0.5, 1.0])),
+            (state("stone_slab", 8), Some([0.0, 0.5, 0.0, 1.0, 1.0, 1.0])),
+            (state("snow_layer", 2), Some([0.0, 0.0, 0.0, 1.0, 0.25, 1.0])),
+            (state("oak_stairs", 5), Some([0.0, 0.0, 0.0, 1.0, 1.0, 1.0])),
+            (state("fence", 0), Some([0.375, 0.0, 0.375, 0.625, 1.5, 0.625])),
+            (state("cobblestone_wall", 0), Some([0.375, 0.0, 0.375, 0.625, 1.5, 0.625])),
+            (state("fence_gate", 4), None),
+            (state("trapdoor", 8), Some([0.0, 0.8125, 0.0, 1.0, 1.0, 1.0])),
+            // Closed doors, facing east, south, west and north
+            (state("wooden_door", 0), Some([0.0, 0.0, 0.0, 0.1875, 1.0, 1.0])),
+            (state("wooden_door", 1), Some([0.0, 0.0, 0.0, 1.0, 1.0, 0.1875])),
+            (state("iron_door", 2), Some([0.8125, 0.0, 0.0, 1.0, 1.0, 1.0])),
+            (state("iron_door", 3), Some([0.0, 0.0, 0.8125, 1.0, 1.0, 1.0])),
+            // Open doors swing a quarter turn
+            (state("wooden_door", 4), Some([0.0, 0.0, 0.0, 1.0, 1.0, 0.1875])),
+            (state("wooden_door", 7), Some([0.0, 0.0, 0.0, 0.1875, 1.0, 1.0])),
+            (state("wooden_door", 8), None)
+        ];
+        for (bs, expected) in cases.iter() {
+            assert_eq!(bounds(*bs), *expected, "{} {}", bs.name(), bs.meta());
+        }
+    }
+
+    #[test]
+    fn standing_and_passing() {
+        assert!(state("oak_stairs", 0).can_stand_on());
+        assert!(!state("oak_stairs", 0).is_solid());
+        assert!(!state("oak_stairs", 0).is_passable());
+        assert!(!state("fence", 0).can_stand_on());
+        assert!(!state("cobblestone_wall", 0).can_stand_on());
+        assert!(!state("wooden_door", 1).is_passable());
+        assert!(state("wooden_door", 5).is_passable());
+        assert!(!state("lava", 0).is_passable());
+        assert!(!state("cactus", 0).can_stand_on());
+        assert_eq!(state("wooden_door", 6).property("facing"), Some("west"));
+        assert_eq!(state("wooden_door", 6).property("open"), Some("true"));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,65 @@
+use super::{registry, BlockState};
+use crate::PROTOCOL_VERSION;
+
+#[derive(Debug, Clone)]
+pub struct BlockQuery {
+    ids: Vec<u16>,
+    properties: Vec<(String, String)>
+}
+
+impl BlockQuery {
+    pub fn parse(query: &str) -> Option<BlockQuery> {
+        let query = query.trim();
+        let (name, properties) = match query.find('[') {
+            Some(idx) => {
+                if !query.ends_with(']') {
+                    return None;
+                }
+                (&query[..idx], parse_properties(&query[idx + 1 .. query.len() - 1])?)
+            }
+            None => (query, Vec::new())
+        };
+
+        let name = name.trim().trim_start_matches("minecraft:");
+        if name.is_empty() {
+            return None;
+        }
+
+        let ids: Vec<u16> = registry(PROTOCOL_VERSION)?.iter()
+            .filter(|b| glob_matches(name.as_bytes(), b.name().as_bytes()))
+            .map(|b| b.id())
+            .collect();
+        if ids.is_empty() {
+            return None;
+        }
+
+        Some(BlockQuery {
+            ids,
+            properties
+        })
+    }
+
+    pub fn matches(&self, state: BlockState) -> bool {
+        self.ids.binary_search(&state.id()).is_ok() &&
+            self.properties.iter().all(|(key, value)| state.property(key) == Some(value.as_ref()))
+    }
+}
+
+fn parse_properties(properties: &str) -> Option<Vec<(String, String)>> {
+    properties.split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let idx = p.find('=')?;
+            Some((p[..idx].trim().to_owned(), p[idx + 1 ..].trim().to_owned()))
+        })
+        .collect()
+}
+
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| glob_matches(rest, &name[skip..])),
+        Some((c, rest)) => name.split_first().is_some_and(|(n, name_rest)| c == n && glob_matches(rest, name_rest))
+    }
+}
@@ -0,0 +1,262 @@
+use super::{block, BlockInfo, CLIMBABLE, DANGEROUS, LIQUID, TRANSPARENT};
+use super::Shape::*;
+use super::Tool::*;
+use super::ToolTier::*;
+
+// Block table for protocol 340 (Minecraft 1.12.2), sorted by id.
+pub static BLOCKS: [BlockInfo; 254] = [
+    block(0, "air", 0.0, None, None, TRANSPARENT, Empty),
+    block(1, "stone", 1.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(2, "grass", 0.6, Some(Shovel), None, 0, Full),
+    block(3, "dirt", 0.5, Some(Shovel), None, 0, Full),
+    block(4, "cobblestone", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(5, "planks", 2.0, Some(Axe), None, 0, Full),
+    block(6, "sapling", 0.0, None, None, TRANSPARENT, Empty),
+    block(7, "bedrock", -1.0, None, None, 0, Full),
+    block(8, "flowing_water", 100.0, None, None, TRANSPARENT | LIQUID, Empty),
+    block(9, "water", 100.0, None, None, TRANSPARENT | LIQUID, Empty),
+    block(10, "flowing_lava", 100.0, None, None, TRANSPARENT | LIQUID | DANGEROUS, Empty),
+    block(11, "lava", 100.0, None, None, TRANSPARENT | LIQUID | DANGEROUS, Empty),
+    block(12, "sand", 0.5, Some(Shovel), None, 0, Full),
+    block(13, "gravel", 0.6, Some(Shovel), None, 0, Full),
+    block(14, "gold_ore", 3.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(15, "iron_ore", 3.0, Some(Pickaxe), Some(Stone), 0, Full),
+    block(16, "coal_ore", 3.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(17, "log", 2.0, Some(Axe), None, 0, Full),
+    block(18, "leaves", 0.2, Some(Shears), None, TRANSPARENT, Full),
+    block(19, "sponge", 0.6, None, None, 0, Full),
+    block(20, "glass", 0.3, None, None, TRANSPARENT, Full),
+    block(21, "lapis_ore", 3.0, Some(Pickaxe), Some(Stone), 0, Full),
+    block(22, "lapis_block", 3.0, Some(Pickaxe), Some(Stone), 0, Full),
+    block(23, "dispenser", 3.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(24, "sandstone", 0.8, Some(Pickaxe), Some(Wood), 0, Full),
+    block(25, "noteblock", 0.8, Some(Axe), None, 0, Full),
+    block(26, "bed", 0.2, None, None, TRANSPARENT, Height(0.5625)),
+    block(27, "golden_rail", 0.7, Some(Pickaxe), None, TRANSPARENT, Empty),
+    block(28, "detector_rail", 0.7, Some(Pickaxe), None, TRANSPARENT, Empty),
+    block(29, "sticky_piston", 0.5, Some(Pickaxe), None, 0, Full),
+    block(30, "web", 4.0, Some(Sword), None, TRANSPARENT, Empty),
+    block(31, "tallgrass", 0.0, None, None, TRANSPARENT, Empty),
+    block(32, "deadbush", 0.0, None, None, TRANSPARENT, Empty),
+    block(33, "piston", 0.5, Some(Pickaxe), None, 0, Full),
+    block(34, "piston_head", 0.5, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(35, "wool", 0.8, Some(Shears), None, 0, Full),
+    block(36, "piston_extension", -1.0, None, None, TRANSPARENT, Empty),
+    block(37, "yellow_flower", 0.0, None, None, TRANSPARENT, Empty),
+    block(38, "red_flower", 0.0, None, None, TRANSPARENT, Empty),
+    block(39, "brown_mushroom", 0.0, None, None, TRANSPARENT, Empty),
+    block(40, "red_mushroom", 0.0, None, None, TRANSPARENT, Empty),
+    block(41, "gold_block", 3.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(42, "iron_block", 5.0, Some(Pickaxe), Some(Stone), 0, Full),
+    block(43, "double_stone_slab", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(44, "stone_slab", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Slab),
+    block(45, "brick_block", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(46, "tnt", 0.0, None, None, 0, Full),
+    block(47, "bookshelf", 1.5, Some(Axe), None, 0, Full),
+    block(48, "mossy_cobblestone", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(49, "obsidian", 50.0, Some(Pickaxe), Some(Diamond), 0, Full),
+    block(50, "torch", 0.0, None, None, TRANSPARENT, Empty),
+    block(51, "fire", 0.0, None, None, TRANSPARENT | DANGEROUS, Empty),
+    block(52, "mob_spawner", 5.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Full),
+    block(53, "oak_stairs", 2.0, Some(Axe), None, TRANSPARENT, Stairs),
+    block(54, "chest", 2.5, Some(Axe), None, TRANSPARENT, Height(0.875)),
+    block(55, "redstone_wire", 0.0, None, None, TRANSPARENT, Empty),
+    block(56, "diamond_ore", 3.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(57, "diamond_block", 5.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(58, "crafting_table", 2.5, Some(Axe), None, 0, Full),
+    block(59, "wheat", 0.0, None, None, TRANSPARENT, Empty),
+    block(60, "farmland", 0.6, Some(Shovel), None, TRANSPARENT, Height(0.9375)),
+    block(61, "furnace", 3.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(62, "lit_furnace", 3.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(63, "standing_sign", 1.0, Some(Axe), None, TRANSPARENT, Empty),
+    block(64, "wooden_door", 3.0, Some(Axe), None, TRANSPARENT, Door),
+    block(65, "ladder", 0.4, Some(Axe), None, TRANSPARENT | CLIMBABLE, Empty),
+    block(66, "rail", 0.7, Some(Pickaxe), None, TRANSPARENT, Empty),
+    block(67, "stone_stairs", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(68, "wall_sign", 1.0, Some(Axe), None, TRANSPARENT, Empty),
+    block(69, "lever", 0.5, None, None, TRANSPARENT, Empty),
+    block(70, "stone_pressure_plate", 0.5, Some(Pickaxe), Some(Wood), TRANSPARENT, Empty),
+    block(71, "iron_door", 5.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Door),
+    block(72, "wooden_pressure_plate", 0.5, Some(Axe), None, TRANSPARENT, Empty),
+    block(73, "redstone_ore", 3.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(74, "lit_redstone_ore", 3.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(75, "unlit_redstone_torch", 0.0, None, None, TRANSPARENT, Empty),
+    block(76, "redstone_torch", 0.0, None, None, TRANSPARENT, Empty),
+    block(77, "stone_button", 0.5, Some(Pickaxe), None, TRANSPARENT, Empty),
+    block(78, "snow_layer", 0.1, Some(Shovel), Some(Wood), TRANSPARENT, Layers),
+    block(79, "ice", 0.5, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(80, "snow", 0.2, Some(Shovel), Some(Wood), 0, Full),
+    block(81, "cactus", 0.4, None, None, TRANSPARENT | DANGEROUS, Height(0.9375)),
+    block(82, "clay", 0.6, Some(Shovel), None, 0, Full),
+    block(83, "reeds", 0.0, None, None, TRANSPARENT, Empty),
+    block(84, "jukebox", 2.0, Some(Axe), None, 0, Full),
+    block(85, "fence", 2.0, Some(Axe), None, TRANSPARENT, Fence),
+    block(86, "pumpkin", 1.0, Some(Axe), None, 0, Full),
+    block(87, "netherrack", 0.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(88, "soul_sand", 0.5, Some(Shovel), None, 0, Height(0.875)),
+    block(89, "glowstone", 0.3, None, None, TRANSPARENT, Full),
+    block(90, "portal", -1.0, None, None, TRANSPARENT, Empty),
+    block(91, "lit_pumpkin", 1.0, Some(Axe), None, 0, Full),
+    block(92, "cake", 0.5, None, None, TRANSPARENT, Height(0.5)),
+    block(93, "unpowered_repeater", 0.0, None, None, TRANSPARENT, Height(0.125)),
+    block(94, "powered_repeater", 0.0, None, None, TRANSPARENT, Height(0.125)),
+    block(95, "stained_glass", 0.3, None, None, TRANSPARENT, Full),
+    block(96, "trapdoor", 3.0, Some(Axe), None, TRANSPARENT, Trapdoor),
+    block(97, "monster_egg", 0.75, Some(Pickaxe), None, 0, Full),
+    block(98, "stonebrick", 1.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(99, "brown_mushroom_block", 0.2, Some(Axe), None, 0, Full),
+    block(100, "red_mushroom_block", 0.2, Some(Axe), None, 0, Full),
+    block(101, "iron_bars", 5.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Full),
+    block(102, "glass_pane", 0.3, None, None, TRANSPARENT, Full),
+    block(103, "melon_block", 1.0, Some(Axe), None, 0, Full),
+    block(104, "pumpkin_stem", 0.0, None, None, TRANSPARENT, Empty),
+    block(105, "melon_stem", 0.0, None, None, TRANSPARENT, Empty),
+    block(106, "vine", 0.2, Some(Shears), None, TRANSPARENT | CLIMBABLE, Empty),
+    block(107, "fence_gate", 2.0, Some(Axe), None, TRANSPARENT, Gate),
+    block(108, "brick_stairs", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(109, "stone_brick_stairs", 1.5, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(110, "mycelium", 0.6, Some(Shovel), None, 0, Full),
+    block(111, "waterlily", 0.0, None, None, TRANSPARENT, Height(0.015625)),
+    block(112, "nether_brick", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(113, "nether_brick_fence", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Fence),
+    block(114, "nether_brick_stairs", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(115, "nether_wart", 0.0, None, None, TRANSPARENT, Empty),
+    block(116, "enchanting_table", 5.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Height(0.75)),
+    block(117, "brewing_stand", 0.5, Some(Pickaxe), Some(Wood), TRANSPARENT, Height(0.875)),
+    block(118, "cauldron", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Full),
+    block(119, "end_portal", -1.0, None, None, TRANSPARENT, Empty),
+    block(120, "end_portal_frame", -1.0, None, None, TRANSPARENT, Height(0.8125)),
+    block(121, "end_stone", 3.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(122, "dragon_egg", 3.0, None, None, TRANSPARENT, Full),
+    block(123, "redstone_lamp", 0.3, None, None, 0, Full),
+    block(124, "lit_redstone_lamp", 0.3, None, None, 0, Full),
+    block(125, "double_wooden_slab", 2.0, Some(Axe), None, 0, Full),
+    block(126, "wooden_slab", 2.0, Some(Axe), None, TRANSPARENT, Slab),
+    block(127, "cocoa", 0.2, Some(Axe), None, TRANSPARENT, Empty),
+    block(128, "sandstone_stairs", 0.8, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(129, "emerald_ore", 3.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(130, "ender_chest", 22.5, Some(Pickaxe), Some(Wood), TRANSPARENT, Height(0.875)),
+    block(131, "tripwire_hook", 0.0, None, None, TRANSPARENT, Empty),
+    block(132, "tripwire", 0.0, None, None, TRANSPARENT, Empty),
+    block(133, "emerald_block", 5.0, Some(Pickaxe), Some(Iron), 0, Full),
+    block(134, "spruce_stairs", 2.0, Some(Axe), None, TRANSPARENT, Stairs),
+    block(135, "birch_stairs", 2.0, Some(Axe), None, TRANSPARENT, Stairs),
+    block(136, "jungle_stairs", 2.0, Some(Axe), None, TRANSPARENT, Stairs),
+    block(137, "command_block", -1.0, None, None, 0, Full),
+    block(138, "beacon", 3.0, None, None, TRANSPARENT, Full),
+    block(139, "cobblestone_wall", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Fence),
+    block(140, "flower_pot", 0.0, None, None, TRANSPARENT, Height(0.375)),
+    block(141, "carrots", 0.0, None, None, TRANSPARENT, Empty),
+    block(142, "potatoes", 0.0, None, None, TRANSPARENT, Empty),
+    block(143, "wooden_button", 0.5, Some(Axe), None, TRANSPARENT, Empty),
+    block(144, "skull", 1.0, None, None, TRANSPARENT, Height(0.5)),
+    block(145, "anvil", 5.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Full),
+    block(146, "trapped_chest", 2.5, Some(Axe), None, TRANSPARENT, Height(0.875)),
+    block(147, "light_weighted_pressure_plate", 0.5, Some(Pickaxe), Some(Wood), TRANSPARENT, Empty),
+    block(148, "heavy_weighted_pressure_plate", 0.5, Some(Pickaxe), Some(Wood), TRANSPARENT, Empty),
+    block(149, "unpowered_comparator", 0.0, None, None, TRANSPARENT, Height(0.125)),
+    block(150, "powered_comparator", 0.0, None, None, TRANSPARENT, Height(0.125)),
+    block(151, "daylight_detector", 0.2, Some(Axe), None, TRANSPARENT, Height(0.375)),
+    block(152, "redstone_block", 5.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(153, "quartz_ore", 3.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(154, "hopper", 3.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Full),
+    block(155, "quartz_block", 0.8, Some(Pickaxe), Some(Wood), 0, Full),
+    block(156, "quartz_stairs", 0.8, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(157, "activator_rail", 0.7, Some(Pickaxe), None, TRANSPARENT, Empty),
+    block(158, "dropper", 3.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(159, "stained_hardened_clay", 1.25, Some(Pickaxe), Some(Wood), 0, Full),
+    block(160, "stained_glass_pane", 0.3, None, None, TRANSPARENT, Full),
+    block(161, "leaves2", 0.2, Some(Shears), None, TRANSPARENT, Full),
+    block(162, "log2", 2.0, Some(Axe), None, 0, Full),
+    block(163, "acacia_stairs", 2.0, Some(Axe), None, TRANSPARENT, Stairs),
+    block(164, "dark_oak_stairs", 2.0, Some(Axe), None, TRANSPARENT, Stairs),
+    block(165, "slime", 0.0, None, None, TRANSPARENT, Full),
+    block(166, "barrier", -1.0, None, None, TRANSPARENT, Full),
+    block(167, "iron_trapdoor", 5.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Trapdoor),
+    block(168, "prismarine", 1.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(169, "sea_lantern", 0.3, None, None, 0, Full),
+    block(170, "hay_block", 0.5, None, None, 0, Full),
+    block(171, "carpet", 0.1, None, None, TRANSPARENT, Height(0.0625)),
+    block(172, "hardened_clay", 1.25, Some(Pickaxe), Some(Wood), 0, Full),
+    block(173, "coal_block", 5.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(174, "packed_ice", 0.5, Some(Pickaxe), None, 0, Full),
+    block(175, "double_plant", 0.0, None, None, TRANSPARENT, Empty),
+    block(176, "standing_banner", 1.0, Some(Axe), None, TRANSPARENT, Empty),
+    block(177, "wall_banner", 1.0, Some(Axe), None, TRANSPARENT, Empty),
+    block(178, "daylight_detector_inverted", 0.2, Some(Axe), None, TRANSPARENT, Height(0.375)),
+    block(179, "red_sandstone", 0.8, Some(Pickaxe), Some(Wood), 0, Full),
+    block(180, "red_sandstone_stairs", 0.8, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(181, "double_stone_slab2", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(182, "stone_slab2", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Slab),
+    block(183, "spruce_fence_gate", 2.0, Some(Axe), None, TRANSPARENT, Gate),
+    block(184, "birch_fence_gate", 2.0, Some(Axe), None, TRANSPARENT, Gate),
+    block(185, "jungle_fence_gate", 2.0, Some(Axe), None, TRANSPARENT, Gate),
+    block(186, "dark_oak_fence_gate", 2.0, Some(Axe), None, TRANSPARENT, Gate),
+    block(187, "acacia_fence_gate", 2.0, Some(Axe), None, TRANSPARENT, Gate),
+    block(188, "spruce_fence", 2.0, Some(Axe), None, TRANSPARENT, Fence),
+    block(189, "birch_fence", 2.0, Some(Axe), None, TRANSPARENT, Fence),
+    block(190, "jungle_fence", 2.0, Some(Axe), None, TRANSPARENT, Fence),
+    block(191, "dark_oak_fence", 2.0, Some(Axe), None, TRANSPARENT, Fence),
+    block(192, "acacia_fence", 2.0, Some(Axe), None, TRANSPARENT, Fence),
+    block(193, "spruce_door", 3.0, Some(Axe), None, TRANSPARENT, Door),
+    block(194, "birch_door", 3.0, Some(Axe), None, TRANSPARENT, Door),
+    block(195, "jungle_door", 3.0, Some(Axe), None, TRANSPARENT, Door),
+    block(196, "acacia_door", 3.0, Some(Axe), None, TRANSPARENT, Door),
+    block(197, "dark_oak_door", 3.0, Some(Axe), None, TRANSPARENT, Door),
+    block(198, "end_rod", 0.0, None, None, TRANSPARENT, Empty),
+    block(199, "chorus_plant", 0.4, Some(Axe), None, TRANSPARENT, Full),
+    block(200, "chorus_flower", 0.4, Some(Axe), None, TRANSPARENT, Full),
+    block(201, "purpur_block", 1.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(202, "purpur_pillar", 1.5, Some(Pickaxe), Some(Wood), 0, Full),
+    block(203, "purpur_stairs", 1.5, Some(Pickaxe), Some(Wood), TRANSPARENT, Stairs),
+    block(204, "purpur_double_slab", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(205, "purpur_slab", 2.0, Some(Pickaxe), Some(Wood), TRANSPARENT, Slab),
+    block(206, "end_bricks", 0.8, Some(Pickaxe), Some(Wood), 0, Full),
+    block(207, "beetroots", 0.0, None, None, TRANSPARENT, Empty),
+    block(208, "grass_path", 0.65, Some(Shovel), None, TRANSPARENT, Height(0.9375)),
+    block(209, "end_gateway", -1.0, None, None, TRANSPARENT, Empty),
+    block(210, "repeating_command_block", -1.0, None, None, 0, Full),
+    block(211, "chain_command_block", -1.0, None, None, 0, Full),
+    block(212, "frosted_ice", 0.5, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(213, "magma", 0.5, Some(Pickaxe), Some(Wood), DANGEROUS, Full),
+    block(214, "nether_wart_block", 1.0, None, None, 0, Full),
+    block(215, "red_nether_brick", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(216, "bone_block", 2.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(217, "structure_void", 0.0, None, None, TRANSPARENT, Empty),
+    block(218, "observer", 3.0, Some(Pickaxe), Some(Wood), 0, Full),
+    block(219, "white_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(220, "orange_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(221, "magenta_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(222, "light_blue_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(223, "yellow_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(224, "lime_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(225, "pink_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(226, "gray_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(227, "silver_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(228, "cyan_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(229, "purple_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(230, "blue_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(231, "brown_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(232, "green_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(233, "red_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(234, "black_shulker_box", 2.0, Some(Pickaxe), None, TRANSPARENT, Full),
+    block(235, "white_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(236, "orange_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(237, "magenta_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(238, "light_blue_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(239, "yellow_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(240, "lime_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(241, "pink_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(242, "gray_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(243, "silver_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(244, "cyan_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(245, "purple_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(246, "blue_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(247, "brown_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(248, "green_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(249, "red_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(250, "black_glazed_terracotta", 1.4, Some(Pickaxe), Some(Wood), 0, Full),
+    block(251, "concrete", 1.8, Some(Pickaxe), Some(Wood), 0, Full),
+    block(252, "concrete_powder", 0.5, Some(Shovel), None, 0, Full),
+    block(255, "structure_block", -1.0, None, None, 0, Full),
+];
\ No newline at end of file
@@ -0,0 +1,574 @@
+use bytes::Buf;
+use crate::blocks::BlockState;
+use crate::geom::LocalAddr;
+use nbt::{DecodeError, DecodeResult};
+use std::collections::HashMap;
+use std::mem::size_of;
+
+pub const SECTION_COUNT: usize = 16;
+pub const SECTION_VOLUME: usize = 4096;
+
+const MIN_PALETTE_BITS: u8 = 4;
+const MAX_PALETTE_BITS: u8 = 8;
+const GLOBAL_BITS: u8 = 13;
+
+pub struct Chunk {
+    sections: Vec<Option<Section>>,
+    damage: HashMap<u16, u8>
+}
+
+impl Chunk {
+    pub fn new() -> Chunk {
+        Chunk {
+            sections: (0..SECTION_COUNT).map(|_| None).collect(),
+            damage: HashMap::default()
+        }
+    }
+
+    // Only the overworld sends skylight. The chunk is left as it was if the
+    // section can't be decoded.
+    pub fn load_section<B: Buf>(&mut self, section_y: u8, data: &mut B, has_skylight: bool) -> DecodeResult<()> {
+        if section_y as usize >= SECTION_COUNT {
+            return Err(DecodeError::InvalidValue("section", section_y as i64));
+        }
+        let blocks = BlockStorage::decode(data)?;
+        let mut light = NibbleArray::new(0);
+        need(data, light.data.len())?;
+        data.copy_to_slice(&mut light.data);
+        let mut skylight = NibbleArray::new(if has_skylight { 15 } else { 0 });
+        if has_skylight {
+            need(data, skylight.data.len())?;
+            data.copy_to_slice(&mut skylight.data);
+        }
+
+        self.sections[section_y as usize] = Some(Section {
+            blocks,
+            light,
+            skylight
+        });
+        Ok(())
+    }
+
+    fn section(&self, LocalAddr(addr): LocalAddr) -> Option<&Section> {
+        self.sections[addr as usize >> 12].as_ref()
+    }
+
+    fn section_mut(&mut self, LocalAddr(addr): LocalAddr) -> &mut Section {
+        self.sections[addr as usize >> 12].get_or_insert_with(Section::new)
+    }
+
+    pub fn block_state(&self, addr: LocalAddr) -> BlockState {
+        self.section(addr).map_or(BlockState(0), |s| s.blocks.get(section_idx(addr)))
+    }
+
+    pub fn set_block_state(&mut self, addr: LocalAddr, val: BlockState) {
+        if self.block_state(addr) == val {
+            return;
+        }
+        self.section_mut(addr).blocks.set(section_idx(addr), val);
+    }
+
+    pub fn damage(&self, LocalAddr(addr): LocalAddr) -> u8 {
+        self.damage.get(&addr).cloned().unwrap_or(0)
+    }
+
+    pub fn set_damage(&mut self, LocalAddr(addr): LocalAddr, val: u8) {
+        if val == 0 {
+            self.damage.remove(&addr);
+        } else {
+            self.damage.insert(addr, val);
+        }
+    }
+
+    pub fn light_level(&self, addr: LocalAddr) -> u8 {
+        self.section(addr).map_or(0, |s| s.light.get(section_idx(addr)))
+    }
+
+    pub fn set_light_level(&mut self, addr: LocalAddr, val: u8) {
+        self.section_mut(addr).light.set(section_idx(addr), val);
+    }
+
+    pub fn skylight_level(&self, addr: LocalAddr) -> u8 {
+        self.section(addr).map_or(15, |s| s.skylight.get(section_idx(addr)))
+    }
+
+    pub fn set_skylight_level(&mut self, addr: LocalAddr, val: u8) {
+        self.section_mut(addr).skylight.set(section_idx(addr), val);
+    }
+
+    // Sections whose palette has no matching state are skipped without
+    // looking at their blocks. Sections that outgrew their palette are always
+    // scanned, and so are missing ones when air matches.
+    pub fn find_matching_block_state(&self, pred: impl Fn(BlockState) -> bool, min_y: i32, max_y: i32) -> Vec<LocalAddr> {
+        let min_y = min_y.max(0);
+        let max_y = max_y.min(255);
+        let mut result = Vec::new();
+        if min_y > max_y {
+            return result;
+        }
+        let matches_air = pred(BlockState(0));
+        for section_y in (min_y >> 4)..=(max_y >> 4) {
+            let base = (section_y as usize) << 12;
+            let start = ((min_y - (section_y << 4)).max(0) as usize) << 8;
+            let end = (((max_y - (section_y << 4)).min(15) + 1) as usize) << 8;
+            match self.sections[section_y as usize] {
+                None => if matches_air {
+                    result.extend((start..end).map(|idx| LocalAddr((base | idx) as u16)));
+                },
+                Some(ref section) => section.blocks.find_matching(&pred, start, end, |idx| {
+                    result.push(LocalAddr((base | idx) as u16));
+                })
+            }
+        }
+        result
+    }
+
+    pub fn storage_heap_size(&self) -> usize {
+        let sections: usize = self.sections.iter()
+            .map(|s| s.as_ref().map_or(0, |s| s.heap_size()))
+            .sum();
+        let damage = self.damage.capacity() * size_of::<(u16, u8)>();
+        self.sections.capacity() * size_of::<Option<Section>>() + sections + damage
+    }
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn section_idx(LocalAddr(addr): LocalAddr) -> usize {
+    addr as usize & (SECTION_VOLUME - 1)
+}
+
+struct Section {
+    blocks: BlockStorage,
+    light: NibbleArray,
+    skylight: NibbleArray
+}
+
+impl Section {
+    fn new() -> Section {
+        Section {
+            blocks: BlockStorage::new(),
+            light: NibbleArray::new(0),
+            skylight: NibbleArray::new(15)
+        }
+    }
+
+    fn heap_size(&self) -> usize {
+        self.blocks.heap_size() + self.light.heap_size() + self.skylight.heap_size()
+    }
+}
+
+// Block states packed in the same layout the server sends: `bits` per entry,
+// entries allowed to straddle two longs, indexing into `palette` unless the
+// storage has outgrown it and holds global state ids directly.
+pub struct BlockStorage {
+    bits: u8,
+    palette: Vec<BlockState>,
+    data: Vec<u64>
+}
+
+impl BlockStorage {
+    pub fn new() -> BlockStorage {
+        BlockStorage {
+            bits: MIN_PALETTE_BITS,
+            palette: vec![BlockState(0)],
+            data: vec![0; longs_for(MIN_PALETTE_BITS)]
+        }
+    }
+
+    // Checks every length against the data left and the bits per entry, so
+    // that nothing read later can index out of bounds
+    fn decode<B: Buf>(data: &mut B) -> DecodeResult<BlockStorage> {
+        need(data, 1)?;
+        let bits = data.get_u8();
+        // Global storage holds whole state ids, which take all 13 bits
+        if bits == 0 || (bits > MAX_PALETTE_BITS && bits != GLOBAL_BITS) {
+            return Err(DecodeError::InvalidValue("bits per block", bits as i64));
+        }
+        // The length is sent even for the global palette, where it is always 0
+        let palette_len = read_varint(data)?;
+        if !(0..=1 << MAX_PALETTE_BITS).contains(&palette_len) {
+            return Err(DecodeError::InvalidValue("palette length", palette_len as i64));
+        }
+        let palette = if bits <= MAX_PALETTE_BITS {
+            (0..palette_len)
+                .map(|_| Ok(BlockState(read_varint(data)? as u16)))
+                .collect::<DecodeResult<_>>()?
+        } else {
+            Vec::new()
+        };
+
+        let data_len = read_varint(data)?;
+        if data_len < 0 || data_len as usize != longs_for(bits) {
+            return Err(DecodeError::InvalidValue("data length", data_len as i64));
+        }
+        need(data, data_len as usize * size_of::<u64>())?;
+        let longs = (0..data_len)
+            .map(|_| data.get_u64_be())
+            .collect();
+
+        Ok(BlockStorage {
+            bits,
+            palette,
+            data: longs
+        })
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    fn is_global(&self) -> bool {
+        self.bits > MAX_PALETTE_BITS
+    }
+
+    pub fn get(&self, idx: usize) -> BlockState {
+        let raw = self.get_raw(idx);
+        if self.is_global() {
+            BlockState(raw as u16)
+        } else {
+            self.palette.get(raw as usize).cloned().unwrap_or(BlockState(0))
+        }
+    }
+
+    pub fn set(&mut self, idx: usize, val: BlockState) {
+        let raw = if self.is_global() {
+            val.0 as u64
+        } else {
+            match self.palette.iter().position(|bs| *bs == val) {
+                Some(raw) => raw as u64,
+                None => {
+                    if self.palette.len() >= 1 << self.bits {
+                        self.grow();
+                        return self.set(idx, val);
+                    }
+                    self.palette.push(val);
+                    (self.palette.len() - 1) as u64
+                }
+            }
+        };
+        self.set_raw(idx, raw);
+    }
+
+    fn grow(&mut self) {
+        let new_bits = if self.bits < MAX_PALETTE_BITS {
+            self.bits + 1
+        } else {
+            GLOBAL_BITS
+        };
+        let mut grown = BlockStorage {
+            bits: new_bits,
+            palette: Vec::new(),
+            data: vec![0; longs_for(new_bits)]
+        };
+        for idx in 0..SECTION_VOLUME {
+            let raw = self.get_raw(idx);
+            let raw = if grown.is_global() {
+                self.palette[raw as usize].0 as u64
+            } else {
+                raw
+            };
+            grown.set_raw(idx, raw);
+        }
+        if !grown.is_global() {
+            grown.palette = std::mem::take(&mut self.palette);
+        }
+        *self = grown;
+    }
+
+    // Calls `found` with the index of every entry in start..end matching
+    // `pred`, consulting the palette first
+    fn find_matching(&self, pred: impl Fn(BlockState) -> bool, start: usize, end: usize, mut found: impl FnMut(usize)) {
+        if self.is_global() {
+            for idx in start..end {
+                if pred(self.get(idx)) {
+                    found(idx);
+                }
+            }
+            return;
+        }
+        // Entries past the end of the palette read as air
+        let matches_air = pred(BlockState(0));
+        let matching: Vec<bool> = self.palette.iter().map(|bs| pred(*bs)).collect();
+        if !matches_air && !matching.contains(&true) {
+            return;
+        }
+        for idx in start..end {
+            if matching.get(self.get_raw(idx) as usize).cloned().unwrap_or(matches_air) {
+                found(idx);
+            }
+        }
+    }
+
+    fn get_raw(&self, idx: usize) -> u64 {
+        let bits = self.bits as usize;
+        let mask = (1u64 << bits) - 1;
+        let bit = idx * bits;
+        let long = bit / 64;
+        let offset = bit % 64;
+        let mut val = self.data[long] >> offset;
+        if offset + bits > 64 {
+            val |= self.data[long + 1] << (64 - offset);
+        }
+        val & mask
+    }
+
+    fn set_raw(&mut self, idx: usize, val: u64) {
+        let bits = self.bits as usize;
+        let mask = (1u64 << bits) - 1;
+        let bit = idx * bits;
+        let long = bit / 64;
+        let offset = bit % 64;
+        self.data[long] = (self.data[long] & !(mask << offset)) | ((val & mask) << offset);
+        if offset + bits > 64 {
+            let spill = 64 - offset;
+            self.data[long + 1] = (self.data[long + 1] & !(mask >> spill)) | ((val & mask) >> spill);
+        }
+    }
+
+    fn heap_size(&self) -> usize {
+        self.palette.capacity() * size_of::<BlockState>() + self.data.capacity() * size_of::<u64>()
+    }
+}
+
+impl Default for BlockStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn longs_for(bits: u8) -> usize {
+    (SECTION_VOLUME * bits as usize).div_ceil(64)
+}
+
+struct NibbleArray {
+    data: Vec<u8>
+}
+
+impl NibbleArray {
+    fn new(default: u8) -> NibbleArray {
+        NibbleArray {
+            data: vec![default | (default << 4); SECTION_VOLUME / 2]
+        }
+    }
+
+    fn get(&self, idx: usize) -> u8 {
+        let byte = self.data[idx / 2];
+        if idx.is_multiple_of(2) {
+            byte & 0x0F
+        } else {
+            byte >> 4
+        }
+    }
+
+    fn set(&mut self, idx: usize, val: u8) {
+        let byte = &mut self.data[idx / 2];
+        if idx.is_multiple_of(2) {
+            *byte = (*byte & 0xF0) | (val & 0x0F);
+        } else {
+            *byte = (*byte & 0x0F) | (val << 4);
+        }
+    }
+
+    fn heap_size(&self) -> usize {
+        self.data.capacity()
+    }
+}
+
+fn need<B: Buf>(buf: &B, needed: usize) -> DecodeResult<()> {
+    if buf.remaining() < needed {
+        return Err(DecodeError::UnexpectedEnd(needed, buf.remaining()));
+    }
+    Ok(())
+}
+
+fn read_varint<B: Buf>(buf: &mut B) -> DecodeResult<i32> {
+    let mut result = 0;
+    for read in 0..5 {
+        need(buf, 1)?;
+        let byte = buf.get_u8();
+        result |= (byte as i32 & 0x7F) << (read * 7);
+
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err(DecodeError::VarNumTooLong)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, Bytes, IntoBuf};
+    use super::*;
+
+    fn put_varint(buf: &mut Vec<u8>, mut val: u32) {
+        loop {
+            let byte = (val & 0x7F) as u8;
+            val >>= 7;
+            if val == 0 {
+                buf.put_u8(byte);
+                return;
+            }
+            buf.put_u8(byte | 0x80);
+        }
+    }
+
+    // One section as 1.12 sends it, with skylight
+    fn put_section(buf: &mut Vec<u8>, bits: u8, palette: &[u16], raw_at: impl Fn(usize) -> u64) {
+        buf.put_u8(bits);
+        put_varint(buf, palette.len() as u32);
+        for state in palette {
+            put_varint(buf, *state as u32);
+        }
+        let mut storage = BlockStorage {
+            bits,
+            palette: Vec::new(),
+            data: vec![0; longs_for(bits)]
+        };
+        for idx in 0..SECTION_VOLUME {
+            storage.set_raw(idx, raw_at(idx));
+        }
+        put_varint(buf, storage.data.len() as u32);
+        for long in storage.data {
+            buf.put_u64_be(long);
+        }
+        buf.extend(std::iter::repeat_n(0x00u8, SECTION_VOLUME / 2));
+        buf.extend(std::iter::repeat_n(0xFFu8, SECTION_VOLUME / 2));
+    }
+
+    #[test]
+    fn decodes_global_palette_section_followed_by_another() {
+        let mut buf = Vec::new();
+        let global = |idx: usize| (((idx % 200 + 1) << 4) | (idx % 16)) as u64;
+        put_section(&mut buf, GLOBAL_BITS, &[], global);
+        put_section(&mut buf, 4, &[0, 1 << 4, 3 << 4], |idx| (idx % 3) as u64);
+
+        let mut chunk = Chunk::new();
+        let mut data = Bytes::from(buf).into_buf();
+        chunk.load_section(0, &mut data, true).unwrap();
+        chunk.load_section(1, &mut data, true).unwrap();
+        assert_eq!(data.remaining(), 0);
+
+        for idx in 0..SECTION_VOLUME {
+            assert_eq!(chunk.block_state(LocalAddr(idx as u16)), BlockState(global(idx) as u16));
+            let expected = [BlockState(0), BlockState(1 << 4), BlockState(3 << 4)][idx % 3];
+            assert_eq!(chunk.block_state(LocalAddr(((1 << 12) | idx) as u16)), expected);
+            assert_eq!(chunk.skylight_level(LocalAddr(((1 << 12) | idx) as u16)), 15);
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_sections() {
+        let mut buf = Vec::new();
+        put_section(&mut buf, 5, &[0, 1 << 4], |idx| (idx % 2) as u64);
+        let light_start = buf.len() - SECTION_VOLUME;
+        for len in &[0, 1, 2, 3, 4, 100, light_start - 1, light_start, buf.len() - 1] {
+            let mut chunk = Chunk::new();
+            let mut data = Bytes::from(&buf[..*len]).into_buf();
+            match chunk.load_section(3, &mut data, true) {
+                Err(DecodeError::UnexpectedEnd(..)) => (),
+                res => panic!("expected UnexpectedEnd at {} bytes, got {:?}", len, res)
+            }
+            assert!(chunk.sections[3].is_none());
+        }
+
+        // Without skylight the section ends before it
+        let mut chunk = Chunk::new();
+        let mut data = Bytes::from(&buf[..buf.len() - SECTION_VOLUME / 2]).into_buf();
+        chunk.load_section(3, &mut data, false).unwrap();
+        assert_eq!(chunk.block_state(LocalAddr::new(1, 48, 0)), BlockState(1 << 4));
+    }
+
+    #[test]
+    fn rejects_malformed_sections() {
+        let decode = |buf: Vec<u8>| Chunk::new().load_section(0, &mut Bytes::from(buf).into_buf(), false);
+        for bits in &[0, 9, 12, 14, 255] {
+            match decode(vec![*bits, 0, 0]) {
+                Err(DecodeError::InvalidValue("bits per block", _)) => (),
+                res => panic!("expected invalid bits {}, got {:?}", bits, res)
+            }
+        }
+
+        // Varints end after at most five bytes
+        match decode(vec![4, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]) {
+            Err(DecodeError::VarNumTooLong) => (),
+            res => panic!("expected VarNumTooLong, got {:?}", res)
+        }
+        match decode(vec![4, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]) {
+            Err(DecodeError::InvalidValue("palette length", -1)) => (),
+            res => panic!("expected invalid palette length, got {:?}", res)
+        }
+
+        // Fewer longs than 4096 entries need
+        let mut buf = vec![4];
+        put_varint(&mut buf, 1);
+        put_varint(&mut buf, 0);
+        put_varint(&mut buf, 255);
+        buf.extend(std::iter::repeat_n(0, 255 * 8 + SECTION_VOLUME / 2));
+        match decode(buf) {
+            Err(DecodeError::InvalidValue("data length", 255)) => (),
+            res => panic!("expected invalid data length, got {:?}", res)
+        }
+
+        let mut buf = Vec::new();
+        put_section(&mut buf, 4, &[0], |_| 0);
+        match Chunk::new().load_section(16, &mut Bytes::from(buf).into_buf(), true) {
+            Err(DecodeError::InvalidValue("section", 16)) => (),
+            res => panic!("expected invalid section, got {:?}", res)
+        }
+    }
+
+    #[test]
+    fn finds_blocks_by_section_palette() {
+        let stone = BlockState(1 << 4);
+        let dirt = BlockState(3 << 4);
+        let mut chunk = Chunk::new();
+        chunk.set_block_state(LocalAddr::new(1, 2, 3), stone);
+        chunk.set_block_state(LocalAddr::new(4, 40, 5), stone);
+        chunk.set_block_state(LocalAddr::new(4, 41, 5), dirt);
+        chunk.set_block_state(LocalAddr::new(15, 255, 15), stone);
+
+        assert_eq!(chunk.find_matching_block_state(|bs| bs == stone, 0, 255), [LocalAddr::new(1, 2, 3), LocalAddr::new(4, 40, 5), LocalAddr::new(15, 255, 15)]);
+        assert_eq!(chunk.find_matching_block_state(|bs| bs == stone, 3, 254), [LocalAddr::new(4, 40, 5)]);
+        assert_eq!(chunk.find_matching_block_state(|bs| bs == stone, -10, 2), [LocalAddr::new(1, 2, 3)]);
+        assert!(chunk.find_matching_block_state(|bs| bs == dirt, 0, 40).is_empty());
+        assert_eq!(chunk.find_matching_block_state(|bs| bs == dirt, 41, 300), [LocalAddr::new(4, 41, 5)]);
+        assert!(chunk.find_matching_block_state(|bs| bs == stone, 10, 5).is_empty());
+        // Replaced blocks stay in the palette but aren't found any more
+        chunk.set_block_state(LocalAddr::new(4, 41, 5), BlockState(0));
+        assert!(chunk.find_matching_block_state(|bs| bs == dirt, 0, 255).is_empty());
+
+        // Missing sections are all air
+        let air = chunk.find_matching_block_state(|bs| bs == BlockState(0), 100, 111);
+        assert_eq!(air.len(), 12 * 256);
+        assert_eq!(air[0], LocalAddr::new(0, 100, 0));
+        assert_eq!(chunk.find_matching_block_state(|bs| bs == BlockState(0), 2, 2).len(), 255);
+    }
+
+    #[test]
+    fn finds_blocks_in_global_sections() {
+        let mut chunk = Chunk::new();
+        for idx in 0..300u16 {
+            chunk.set_block_state(LocalAddr(idx), BlockState((idx + 1) << 4));
+        }
+        assert_eq!(chunk.find_matching_block_state(|bs| bs == BlockState(17 << 4), 0, 255), [LocalAddr(16)]);
+        assert_eq!(chunk.find_matching_block_state(|bs| bs.id() > 290, 0, 255).len(), 10);
+        assert_eq!(chunk.find_matching_block_state(|bs| bs == BlockState(0), 0, 15).len(), SECTION_VOLUME - 300);
+    }
+
+    #[test]
+    fn palette_grows_to_global() {
+        let mut chunk = Chunk::new();
+        for idx in 0..300u16 {
+            chunk.set_block_state(LocalAddr(idx), BlockState((idx + 1) << 4));
+        }
+        for idx in 0..300u16 {
+            assert_eq!(chunk.block_state(LocalAddr(idx)), BlockState((idx + 1) << 4));
+        }
+        assert_eq!(chunk.block_state(LocalAddr(300)), BlockState(0));
+    }
+}
@@ -0,0 +1,397 @@
+use crate::PROTOCOL_VERSION;
+use crate::watchdog::Watchdog;
+use packets::{ClientPacket, HandshakePacket};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatMode {
+    Enabled,
+    CommandsOnly,
+    Hidden
+}
+
+impl ChatMode {
+    pub fn id(self) -> i32 {
+        match self {
+            ChatMode::Enabled => 0,
+            ChatMode::CommandsOnly => 1,
+            ChatMode::Hidden => 2
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainHand {
+    Left,
+    Right
+}
+
+impl MainHand {
+    pub fn id(self) -> i32 {
+        match self {
+            MainHand::Left => 0,
+            MainHand::Right => 1
+        }
+    }
+}
+
+pub const SKIN_CAPE: u8 = 0x01;
+pub const SKIN_JACKET: u8 = 0x02;
+pub const SKIN_LEFT_SLEEVE: u8 = 0x04;
+pub const SKIN_RIGHT_SLEEVE: u8 = 0x08;
+pub const SKIN_LEFT_PANTS: u8 = 0x10;
+pub const SKIN_RIGHT_PANTS: u8 = 0x20;
+pub const SKIN_HAT: u8 = 0x40;
+pub const SKIN_ALL: u8 = 0x7F;
+
+#[derive(Debug, Clone)]
+pub struct Socks5Proxy {
+    host: String,
+    port: u16,
+    auth: Option<(String, String)>
+}
+
+impl Socks5Proxy {
+    pub fn new<H: Into<String>>(host: H, port: u16) -> Self {
+        Socks5Proxy {
+            host: host.into(),
+            port,
+            auth: None
+        }
+    }
+
+    pub fn auth<U: Into<String>, P: Into<String>>(mut self, username: U, password: P) -> Self {
+        self.auth = Some((username.into(), password.into()));
+        self
+    }
+
+    fn handshake(&self, stream: &mut TcpStream, host: &str, port: u16) -> io::Result<()> {
+        let method = if self.auth.is_some() { 0x02 } else { 0x00 };
+        stream.write_all(&[5, 1, method])?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply)?;
+        if reply[0] != 5 || reply[1] != method {
+            return Err(proxy_error("proxy does not accept the authentication method".to_owned()));
+        }
+
+        if let Some((ref username, ref password)) = self.auth {
+            let mut request = vec![1];
+            push_short_str(&mut request, username)?;
+            push_short_str(&mut request, password)?;
+            stream.write_all(&request)?;
+            stream.read_exact(&mut reply)?;
+            if reply[1] != 0 {
+                return Err(proxy_error("proxy authentication failed".to_owned()));
+            }
+        }
+
+        // Always send the host name, so it is resolved by the proxy
+        let mut request = vec![5, 1, 0, 3];
+        push_short_str(&mut request, host)?;
+        request.push((port >> 8) as u8);
+        request.push(port as u8);
+        stream.write_all(&request)?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply)?;
+        if reply[1] != 0 {
+            return Err(proxy_error(format!("proxy could not connect to {}:{} (code {})", host, port, reply[1])));
+        }
+        let bound_len = match reply[3] {
+            1 => 4,
+            4 => 16,
+            3 => {
+                let mut len = [0u8; 1];
+                stream.read_exact(&mut len)?;
+                len[0] as usize
+            }
+            t => return Err(proxy_error(format!("unknown proxy address type {}", t)))
+        };
+        let mut bound = vec![0; bound_len + 2];
+        stream.read_exact(&mut bound)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectOptions {
+    locale: String,
+    view_distance: u8,
+    chat_mode: ChatMode,
+    chat_colors: bool,
+    skin_parts: u8,
+    main_hand: MainHand,
+    protocol_version: i32,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    silence_timeout: Duration,
+    keepalive_timeout: Duration,
+    bind_address: Option<IpAddr>,
+    proxy: Option<Socks5Proxy>
+}
+
+impl ConnectOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn locale<L: Into<String>>(mut self, locale: L) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    pub fn view_distance(mut self, view_distance: u8) -> Self {
+        self.view_distance = view_distance;
+        self
+    }
+
+    pub fn chat_mode(mut self, chat_mode: ChatMode) -> Self {
+        self.chat_mode = chat_mode;
+        self
+    }
+
+    pub fn chat_colors(mut self, chat_colors: bool) -> Self {
+        self.chat_colors = chat_colors;
+        self
+    }
+
+    pub fn skin_parts(mut self, skin_parts: u8) -> Self {
+        self.skin_parts = skin_parts;
+        self
+    }
+
+    pub fn main_hand(mut self, main_hand: MainHand) -> Self {
+        self.main_hand = main_hand;
+        self
+    }
+
+    pub fn protocol_version(mut self, protocol_version: i32) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    // Timeout of each read while talking to the proxy and logging in. Once
+    // logged in, reads wait for the end of the tick and the watchdog decides
+    // when the server went silent
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    // Give up on the connection when nothing at all arrives for this long
+    pub fn silence_timeout(mut self, timeout: Duration) -> Self {
+        self.silence_timeout = timeout;
+        self
+    }
+
+    // Give up on the connection when the server stops sending keepalives, even
+    // if other packets still arrive
+    pub fn keepalive_timeout(mut self, timeout: Duration) -> Self {
+        self.keepalive_timeout = timeout;
+        self
+    }
+
+    pub fn bind_address(mut self, address: IpAddr) -> Self {
+        self.bind_address = Some(address);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Socks5Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn handshake(&self, host: &str, port: u16) -> HandshakePacket {
+        HandshakePacket::HandshakePacket {
+            version: self.protocol_version,
+            host: host.to_owned(),
+            port,
+            next: 2
+        }
+    }
+
+    pub fn client_settings(&self) -> ClientPacket {
+        ClientPacket::ClientSettings {
+            locale: self.locale.clone(),
+            view_distance: self.view_distance,
+            chat_mode: self.chat_mode.id(),
+            chat_colors: self.chat_colors,
+            displayed_skin: self.skin_parts,
+            main_hand: self.main_hand.id()
+        }
+    }
+
+    pub fn watchdog(&self) -> Watchdog {
+        Watchdog::new(self.silence_timeout, self.keepalive_timeout)
+    }
+
+    // Opens the connection to the server, going through the proxy if one is set
+    pub fn open(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        let stream = match self.proxy {
+            Some(ref proxy) => {
+                let mut stream = self.open_socket((&proxy.host as &str, proxy.port))?;
+                stream.set_read_timeout(self.read_timeout.or(self.connect_timeout))?;
+                proxy.handshake(&mut stream, host, port)?;
+                stream
+            }
+            None => self.open_socket((host, port))?
+        };
+        // Login reads block, don't let a silent server hang them forever
+        stream.set_read_timeout(self.read_timeout.or(Some(self.silence_timeout)))?;
+        Ok(stream)
+    }
+
+    fn open_socket<A: ToSocketAddrs>(&self, address: A) -> io::Result<TcpStream> {
+        let mut last_error = None;
+        for addr in address.to_socket_addrs()? {
+            match self.connect_addr(&addr) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e)
+            }
+        }
+        Err(last_error.unwrap_or_else(|| io::Error::new(ErrorKind::InvalidInput, "could not resolve address")))
+    }
+
+    fn connect_addr(&self, addr: &SocketAddr) -> io::Result<TcpStream> {
+        let domain = if addr.is_ipv4() { Domain::ipv4() } else { Domain::ipv6() };
+        let socket = Socket::new(domain, Type::stream(), Some(Protocol::tcp()))?;
+        if let Some(bind_address) = self.bind_address {
+            socket.bind(&SocketAddr::new(bind_address, 0).into())?;
+        }
+        match self.connect_timeout {
+            Some(timeout) => socket.connect_timeout(&(*addr).into(), timeout)?,
+            None => socket.connect(&(*addr).into())?
+        }
+        Ok(socket.into_tcp_stream())
+    }
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        ConnectOptions {
+            locale: "en-US".to_owned(),
+            view_distance: 4,
+            chat_mode: ChatMode::Enabled,
+            chat_colors: false,
+            skin_parts: 0xFF,
+            main_hand: MainHand::Left,
+            protocol_version: PROTOCOL_VERSION,
+            connect_timeout: None,
+            read_timeout: None,
+            silence_timeout: Duration::from_secs(30),
+            keepalive_timeout: Duration::from_secs(45),
+            bind_address: None,
+            proxy: None
+        }
+    }
+}
+
+fn push_short_str(buf: &mut Vec<u8>, s: &str) -> io::Result<()> {
+    if s.len() > 255 {
+        return Err(proxy_error(format!("{} is too long for a SOCKS5 request", s)));
+    }
+    buf.push(s.len() as u8);
+    buf.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn proxy_error(message: String) -> io::Error {
+    io::Error::other(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    // The user the proxy logged in and the target it was asked to connect to
+    type ProxyRequest = (Option<(String, String)>, String, u16);
+
+    // Accepts one connection and answers the SOCKS5 handshake with the given
+    // CONNECT reply code
+    fn fake_proxy(auth: bool, reply_code: u8) -> (u16, thread::JoinHandle<ProxyRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).unwrap();
+            let method = if auth { 0x02 } else { 0x00 };
+            assert_eq!(greeting, [5, 1, method]);
+            stream.write_all(&[5, method]).unwrap();
+
+            let user = if auth {
+                assert_eq!(read_bytes(&mut stream, 1), [1]);
+                let username = read_short_str(&mut stream);
+                let password = read_short_str(&mut stream);
+                stream.write_all(&[1, 0]).unwrap();
+                Some((username, password))
+            } else {
+                None
+            };
+
+            assert_eq!(read_bytes(&mut stream, 4), [5, 1, 0, 3]);
+            let host = read_short_str(&mut stream);
+            let port = read_bytes(&mut stream, 2);
+            stream.write_all(&[5, reply_code, 0, 1, 10, 0, 0, 1, 0x63, 0xDD]).unwrap();
+            if reply_code == 0 {
+                stream.write_all(b"ok").unwrap();
+            }
+            (user, host, ((port[0] as u16) << 8) | port[1] as u16)
+        });
+        (port, handle)
+    }
+
+    fn read_bytes(stream: &mut TcpStream, len: usize) -> Vec<u8> {
+        let mut buf = vec![0; len];
+        stream.read_exact(&mut buf).unwrap();
+        buf
+    }
+
+    fn read_short_str(stream: &mut TcpStream) -> String {
+        let len = read_bytes(stream, 1)[0] as usize;
+        String::from_utf8(read_bytes(stream, len)).unwrap()
+    }
+
+    fn options(proxy: Socks5Proxy) -> ConnectOptions {
+        ConnectOptions::new()
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(5))
+            .proxy(proxy)
+    }
+
+    #[test]
+    fn socks5_without_auth() {
+        let (port, proxy) = fake_proxy(false, 0);
+        let mut stream = options(Socks5Proxy::new("127.0.0.1", port)).open("mc.example.org", 25565).unwrap();
+        assert_eq!(read_bytes(&mut stream, 2), b"ok");
+        assert_eq!(proxy.join().unwrap(), (None, "mc.example.org".to_owned(), 25565));
+    }
+
+    #[test]
+    fn socks5_with_auth() {
+        let (port, proxy) = fake_proxy(true, 0);
+        let proxy_options = Socks5Proxy::new("127.0.0.1", port).auth("steve", "hunter2");
+        let mut stream = options(proxy_options).open("10.1.2.3", 25566).unwrap();
+        assert_eq!(read_bytes(&mut stream, 2), b"ok");
+        let user = Some(("steve".to_owned(), "hunter2".to_owned()));
+        assert_eq!(proxy.join().unwrap(), (user, "10.1.2.3".to_owned(), 25566));
+    }
+
+    #[test]
+    fn socks5_rejected_connect() {
+        let (port, proxy) = fake_proxy(false, 5);
+        let err = options(Socks5Proxy::new("127.0.0.1", port)).open("mc.example.org", 25565).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert_eq!(err.to_string(), "proxy could not connect to mc.example.org:25565 (code 5)");
+        proxy.join().unwrap();
+    }
+}
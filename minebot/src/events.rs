@@ -1,12 +1,15 @@
-use crate::gamestate::GameState;
+use crate::gamestate::{EntityId, GameState};
+use crate::geom::Position;
 use json::JsonValue;
-use packets::{PlayerListPacket, ServerPacket};
+use packets::{CombatEventPacket, DimensionId, PlayerListPacket, ServerPacket};
 
 #[derive(Clone)]
 pub enum EventMatcher {
     ChatMessage,
     HealthChanged,
-    PlayerListChanged
+    PlayerListChanged,
+    DimensionChanged,
+    Died
 }
 
 impl EventMatcher {
@@ -57,6 +60,29 @@ impl EventMatcher {
                 })
             }
 
+            (EventMatcher::DimensionChanged, ServerPacket::Respawn { dimension, .. }) => {
+                if *dimension != gamestate.dimension() {
+                    Some(Event::DimensionChanged {
+                        new: *dimension,
+                        old: gamestate.dimension()
+                    })
+                } else {
+                    None
+                }
+            }
+
+            (EventMatcher::Died, ServerPacket::CombatEvent { event: CombatEventPacket::EntityDead { player_id, entity_id, message } }) => {
+                if gamestate.is_me(*player_id) {
+                    Some(Event::Died {
+                        position: gamestate.my_position().ok().cloned(),
+                        cause: death_cause(message),
+                        killer: if *entity_id >= 0 { Some(*entity_id) } else { None }
+                    })
+                } else {
+                    None
+                }
+            }
+
             _ => None
         }
     }
@@ -77,6 +103,23 @@ pub enum Event {
     },
     PlayersLeft {
         usernames: Vec<String>
+    },
+    DimensionChanged {
+        new: DimensionId,
+        old: DimensionId
+    },
+    Died {
+        // Unknown when the player died before its position was ever sent
+        position: Option<Position>,
+        cause: String,
+        killer: Option<EntityId>
+    },
+    // Only sent by ReconnectingClient and ThreadedClient
+    ConnectionLost {
+        reason: String
+    },
+    Reconnected {
+        attempts: u32
     }
 }
 
@@ -107,4 +150,13 @@ fn parse_chat(chat: &JsonValue) -> Option<(String, String)> {
         message = chat["with"][1].as_str()?.to_owned()
     }
     Some((player, message))
+}
+
+// Death messages are translatable, the key (e.g. death.attack.zombie) is more
+// useful to match on than the rendered text
+fn death_cause(message: &JsonValue) -> String {
+    message["translate"].as_str()
+        .or_else(|| message["text"].as_str())
+        .unwrap_or("")
+        .to_owned()
 }
\ No newline at end of file
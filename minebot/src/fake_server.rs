@@ -0,0 +1,173 @@
+// A scripted stand-in for a 1.12.2 server, just enough for the client to log
+// in and receive the packets a test sends it
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+pub const CLIENT_SETTINGS: i32 = 0x04;
+pub const CLIENT_KEEP_ALIVE: i32 = 0x0B;
+pub const CLIENT_TELEPORT_CONFIRM: i32 = 0x00;
+pub const CLIENT_POSITION_AND_LOOK: i32 = 0x0E;
+
+pub struct FakeServer {
+    listener: TcpListener
+}
+
+impl FakeServer {
+    pub fn bind() -> Self {
+        FakeServer {
+            listener: TcpListener::bind("127.0.0.1:0").unwrap()
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.listener.local_addr().unwrap().port()
+    }
+
+    // Accepts the next client and takes it through login, up to the first
+    // keepalive being answered
+    pub fn accept(&self) -> Connection {
+        let (stream, _) = self.listener.accept().unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        let mut conn = Connection {
+            stream
+        };
+        // Handshake and LoginStart
+        conn.receive();
+        conn.receive();
+
+        let mut login_success = Vec::new();
+        put_str(&mut login_success, "00000000-0000-0000-0000-000000000001");
+        put_str(&mut login_success, "bot");
+        conn.send(0x02, &login_success);
+
+        let mut abilities = vec![0];
+        abilities.extend_from_slice(&0.05f32.to_be_bytes());
+        abilities.extend_from_slice(&0.1f32.to_be_bytes());
+        conn.send(0x2C, &abilities);
+        conn.expect(CLIENT_SETTINGS);
+
+        conn.keep_alive(1);
+        assert_eq!(conn.expect(CLIENT_KEEP_ALIVE), 1i64.to_be_bytes());
+        conn
+    }
+}
+
+pub struct Connection {
+    stream: TcpStream
+}
+
+impl Connection {
+    pub fn send(&mut self, id: i32, body: &[u8]) {
+        let mut packet = Vec::new();
+        put_varint(&mut packet, id);
+        packet.extend_from_slice(body);
+        let mut frame = Vec::new();
+        put_varint(&mut frame, packet.len() as i32);
+        frame.extend_from_slice(&packet);
+        // The client may already be gone, which the test finds out on its side
+        let _ = self.stream.write_all(&frame);
+    }
+
+    pub fn keep_alive(&mut self, id: i64) {
+        self.send(0x1F, &id.to_be_bytes());
+    }
+
+    // Survival in the overworld
+    pub fn join_game(&mut self, entity_id: i32) {
+        let mut body = entity_id.to_be_bytes().to_vec();
+        body.extend_from_slice(&[0, 0, 0, 0, 0, 2, 20]);
+        put_str(&mut body, "default");
+        body.push(0);
+        self.send(0x23, &body);
+    }
+
+    pub fn teleport(&mut self, x: f64, y: f64, z: f64, teleport_id: i32) {
+        let mut body = Vec::new();
+        for coord in &[x, y, z] {
+            body.extend_from_slice(&coord.to_be_bytes());
+        }
+        body.extend_from_slice(&[0; 8]);
+        body.push(0);
+        put_varint(&mut body, teleport_id);
+        self.send(0x2F, &body);
+    }
+
+    pub fn chat(&mut self, player: &str, message: &str) {
+        let mut json = json::JsonValue::new_object();
+        json["translate"] = "chat.type.text".into();
+        let mut sender = json::JsonValue::new_object();
+        sender["text"] = player.into();
+        json["with"] = vec![sender, message.into()].into();
+        let mut body = Vec::new();
+        put_str(&mut body, &json.dump());
+        body.push(0);
+        self.send(0x0F, &body);
+    }
+
+    pub fn block_change(&mut self, position: u64, block_state: i32) {
+        let mut body = position.to_be_bytes().to_vec();
+        put_varint(&mut body, block_state);
+        self.send(0x0B, &body);
+    }
+
+    pub fn destroy_entities(&mut self, entity_ids: &[i32]) {
+        let mut body = Vec::new();
+        put_varint(&mut body, entity_ids.len() as i32);
+        for entity_id in entity_ids {
+            put_varint(&mut body, *entity_id);
+        }
+        self.send(0x32, &body);
+    }
+
+    // Id and body of the next packet from the client
+    pub fn receive(&mut self) -> (i32, Vec<u8>) {
+        let len = read_varint(&mut self.stream) as usize;
+        let mut packet = vec![0; len];
+        self.stream.read_exact(&mut packet).unwrap();
+        let mut body = &packet[..];
+        let id = read_varint(&mut body);
+        (id, body.to_vec())
+    }
+
+    // Skips packets until one with the given id arrives, returns its body
+    pub fn expect(&mut self, id: i32) -> Vec<u8> {
+        loop {
+            let (got, body) = self.receive();
+            if got == id {
+                return body;
+            }
+        }
+    }
+}
+
+fn put_varint(buf: &mut Vec<u8>, val: i32) {
+    let mut val = val as u32;
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if val == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    put_varint(buf, s.len() as i32);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn read_varint(input: &mut impl Read) -> i32 {
+    let mut result = 0;
+    for read in 0..5 {
+        let mut byte = [0u8];
+        input.read_exact(&mut byte).unwrap();
+        result |= (byte[0] as i32 & 0x7F) << (read * 7);
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+    result
+}
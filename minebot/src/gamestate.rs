@@ -1,10 +1,11 @@
-use bytes::{Buf, Bytes, IntoBuf};
-use crate::geom::{CHUNK_WIDTH, BlockPosition, ChunkAddr, LocalAddr, Position};
+use bytes::{Bytes, IntoBuf};
+use crate::geom::{BlockPosition, ChunkAddr, Cuboid, Distance, LocalAddr, Position, Ray, RayHit, Region, Rotation};
 use crate::blocks::BlockState;
-use packets::{AddPlayer, PlayerListPacket, RemovePlayer, ServerPacket};
-use pathfinding::directed::astar::astar;
+use crate::chunk::Chunk;
+use crate::{Error, Result};
+use crate::path::{self, Goal, Near, Path, PathOptions};
+use packets::{AddPlayer, CombatEventPacket, DimensionId, PlayerListPacket, RemovePlayer, ServerPacket};
 use std::collections::HashMap;
-use std::iter::{repeat, Cloned};
 use uuid::Uuid;
 
 pub struct GameState {
@@ -12,8 +13,14 @@ pub struct GameState {
     my_id: Uuid,
     health: f32,
     food: f32,
-    chunks: HashMap<ChunkAddr, Chunk>,
-    entities: HashMap<EntityId, Entity>
+    dimension: DimensionId,
+    // Only the current dimension has chunks, the ones left behind are
+    // dropped on the way out
+    worlds: HashMap<DimensionId, HashMap<ChunkAddr, Chunk>>,
+    // Blocks changed by the most recently handled packet
+    block_changes: Vec<BlockPosition>,
+    entities: HashMap<EntityId, Entity>,
+    last_death: Option<(DimensionId, Position)>
 }
 
 impl GameState {
@@ -29,26 +36,36 @@ impl GameState {
             my_id,
             health: 10.0,
             food: 10.0,
-            chunks: HashMap::default(),
-            entities: HashMap::default()
+            dimension: DimensionId::Overworld,
+            worlds: HashMap::default(),
+            block_changes: Vec::default(),
+            entities: HashMap::default(),
+            last_death: None
         }
     }
 
-    pub fn handle_packet(&mut self, packet: &ServerPacket) {
+    pub fn handle_packet(&mut self, packet: &ServerPacket) -> Result<()> {
+        self.block_changes.clear();
         match *packet {
             ServerPacket::BlockChange { position, block_state } => {
-                let pos = BlockPosition::new((position >> 38) as i32, (position >> 26 & 0xFFF) as i32, (position & 0x3FFFFFF) as i32);
+                let pos = BlockPosition::from_packed(position);
                 let bs = BlockState(block_state as u16);
                 self.set_block_state(&pos, bs);
             }
             ServerPacket::ChunkData { chunk_x, chunk_z, full_chunk, primary_bitmask, ref data } => {
                 if full_chunk {
-                    self.load_chunk_data(chunk_x, chunk_z, primary_bitmask as u8, data)
+                    self.load_chunk_data(chunk_x, chunk_z, primary_bitmask as u16, data)?;
                 }
             }
-            ServerPacket::JoinGame { entity_id, .. } => {
-                self.players.get_mut(&self.my_id).unwrap().entity_id = Some(entity_id);
+            ServerPacket::JoinGame { entity_id, dimension, .. } => {
+                if let Some(me) = self.players.get_mut(&self.my_id) {
+                    me.entity_id = Some(entity_id);
+                }
                 self.entities.insert(entity_id, Entity::default());
+                self.dimension = dimension;
+            }
+            ServerPacket::Respawn { dimension, .. } => {
+                self.change_dimension(dimension)?;
             }
             ServerPacket::MultiBlockChange { chunk_x, chunk_z, ref records } => {
                 let chunk_addr = ChunkAddr::new(chunk_x, chunk_z);
@@ -64,12 +81,15 @@ impl GameState {
                 }
             }
             ServerPacket::PlayerList { packet: PlayerListPacket::RemovePlayers { ref players } } => {
-                for RemovePlayer { uuid } in players {
+                // Our own entry is kept, accessors rely on it being there
+                let my_id = self.my_id;
+                for RemovePlayer { uuid } in players.iter().filter(|player| player.uuid != my_id) {
                     self.players.remove(&uuid);
                 }
             }
-            ServerPacket::PlayerPositionAndLook {x, y, z, flags, .. } => {
-                let my_position = &mut self.my_entity_mut().position;
+            ServerPacket::PlayerPositionAndLook {x, y, z, yaw, pitch, flags, .. } => {
+                let my_entity = self.my_entity_mut()?;
+                let my_position = &mut my_entity.position;
                 if flags & 0x01 != 0 {
                     my_position.add_x(x);
                 } else {
@@ -85,20 +105,31 @@ impl GameState {
                 } else {
                     my_position.set_z(z);
                 }
-                /*if flags & 0x08 != 0 {
-                    my_orientation.add_yaw(yaw);
+                let my_rotation = &mut my_entity.rotation;
+                if flags & 0x08 != 0 {
+                    my_rotation.add_yaw(yaw);
                 } else {
-                    my_orientation.set_yaw(yaw);
+                    my_rotation.set_yaw(yaw);
                 }
                 if flags & 0x10 != 0 {
-                    my_orientation.add_pitch(pitch);
+                    my_rotation.add_pitch(pitch);
                 } else {
-                    my_orientation.set_pitch(pitch);
-                }*/
+                    my_rotation.set_pitch(pitch);
+                }
+            }
+            ServerPacket::SpawnMob { entity_id, mob_type, .. } => {
+                self.entities.insert(entity_id, Entity::mob(mob_type));
+            }
+            ServerPacket::DestroyEntities { ref entities } => {
+                for destroyed in entities {
+                    self.entities.remove(&destroyed.entity_id);
+                }
             }
             ServerPacket::SpawnPlayer { uuid, entity_id, .. } => {
                 self.entities.insert(entity_id, Entity::default());
-                self.players.get_mut(&uuid).unwrap().set_entity_id(entity_id);
+                self.players.get_mut(&uuid)
+                    .ok_or_else(|| Error::InvalidState(format!("spawned player {} is not in the player list", uuid)))?
+                    .set_entity_id(entity_id);
             }
             ServerPacket::UnloadChunk { chunk_x, chunk_z } => {
                 self.unload_chunk(chunk_x, chunk_z);
@@ -107,12 +138,16 @@ impl GameState {
                 self.health = health / 2.0;
                 self.food = (food as f32) / 2.0;
             }
+            ServerPacket::CombatEvent { event: CombatEventPacket::EntityDead { player_id, .. } } if self.is_me(player_id) => {
+                self.last_death = Some((self.dimension, *self.my_position()?));
+            }
             _ => {}
         };
 
         if let Some(entity_id) = entity_id(packet) {
             self.handle_entity_packet(entity_id, packet);
         }
+        Ok(())
     }
 
     fn handle_entity_packet(&mut self, entity_id: EntityId, packet: &ServerPacket) {
@@ -125,20 +160,71 @@ impl GameState {
         &self.players[&self.my_id].name
     }
 
-    fn my_entity_id(&self) -> EntityId {
-        self.players.get(&self.my_id).unwrap().entity_id.unwrap()
+    fn my_entity_id(&self) -> Result<EntityId> {
+        self.players[&self.my_id].entity_id.ok_or(Error::NotSpawned)
+    }
+
+    pub fn is_me(&self, entity_id: EntityId) -> bool {
+        self.players[&self.my_id].entity_id == Some(entity_id)
+    }
+
+    fn my_entity(&self) -> Result<&Entity> {
+        let entity_id = self.my_entity_id()?;
+        self.entities.get(&entity_id).ok_or(Error::UnknownEntity(entity_id))
     }
 
-    fn my_entity(&self) -> &Entity {
-        self.entities.get(&self.my_entity_id()).unwrap()
+    fn my_entity_mut(&mut self) -> Result<&mut Entity> {
+        let entity_id = self.my_entity_id()?;
+        self.entities.get_mut(&entity_id).ok_or(Error::UnknownEntity(entity_id))
     }
 
-    fn my_entity_mut(&mut self) -> &mut Entity {
-        self.entities.get_mut(&self.my_entity_id()).unwrap()
+    pub fn my_position(&self) -> Result<&Position> {
+        Ok(&self.my_entity()?.position)
     }
 
-    pub fn my_position(&self) -> &Position {
-        &self.my_entity().position
+    pub fn set_my_position(&mut self, position: Position) -> Result<()> {
+        self.my_entity_mut()?.position = position;
+        Ok(())
+    }
+
+    // Whether the player's feet rest on top of a collision box. Fences and
+    // walls reach up into the next block, so the block under the feet is
+    // checked too. Assumed while the block underneath isn't known, as that is
+    // where the server put us.
+    pub fn is_on_ground(&self) -> Result<bool> {
+        let position = self.my_position()?;
+        let feet = position.with_add_y(-0.01).block_position();
+        if self.block_state_at(&feet).is_none() {
+            return Ok(true);
+        }
+        Ok([feet, feet.with_diff(0, -1, 0)].iter().any(|below| {
+            self.block_state_at(below)
+                .and_then(|bs| bs.collision_box())
+                .is_some_and(|collision_box| (below.y() as f64 + collision_box.max().y() - position.y()).abs() < 0.01)
+        }))
+    }
+
+    pub fn my_rotation(&self) -> Result<Rotation> {
+        Ok(self.my_entity()?.rotation)
+    }
+
+    pub fn set_my_rotation(&mut self, rotation: Rotation) -> Result<()> {
+        self.my_entity_mut()?.rotation = rotation;
+        Ok(())
+    }
+
+    pub fn entity_position(&self, entity_id: EntityId) -> Option<Position> {
+        self.entities.get(&entity_id).map(|entity| entity.position)
+    }
+
+    pub fn player_entity_id(&self, name: &str) -> Option<EntityId> {
+        self.players.values()
+            .find(|player| player.name == name)
+            .and_then(|player| player.entity_id)
+    }
+
+    pub fn last_death(&self) -> Option<(DimensionId, Position)> {
+        self.last_death
     }
 
     pub fn health(&self) -> f32 {
@@ -153,28 +239,57 @@ impl GameState {
         self.players.get(id).map(|p| p.name.as_ref())
     }
 
+    // A chunk that fails to decode is not loaded at all
     pub fn load_chunk_data(&mut self, chunk_x: i32, chunk_z: i32, 
-        mut primary_bit_mask: u8, data: &Bytes) {
+        mut primary_bit_mask: u16, data: &Bytes) -> Result<()> {
         trace!("Loading chunk at ({}, {})", chunk_x, chunk_z);
         let addr = ChunkAddr::new(chunk_x, chunk_z);
         let mut data = data.clone().into_buf();
         let mut chunk = Chunk::new();
+        let has_skylight = self.dimension == DimensionId::Overworld;
 
-        let mut y_offset: u8 = 0;
+        let mut section_y: u8 = 0;
         while primary_bit_mask > 0 {
             if primary_bit_mask & 0x01 > 0 {
-                load_single_chunk(&mut chunk, y_offset, &mut data);
+                chunk.load_section(section_y, &mut data, has_skylight)?;
             }
-            y_offset += 16;
+            section_y += 1;
             primary_bit_mask >>= 1;
         }
 
-        self.chunks.insert(addr, chunk);
+        self.worlds.entry(self.dimension).or_default().insert(addr, chunk);
+        Ok(())
+    }
+
+    // Chunks and entities are only tracked in the current dimension, even
+    // when respawning in the same one. The server sends the chunks around the
+    // player again on arrival.
+    fn change_dimension(&mut self, dimension: DimensionId) -> Result<()> {
+        if dimension != self.dimension {
+            info!("Moving from {:?} to {:?}", self.dimension, dimension);
+        }
+        self.worlds.remove(&self.dimension);
+        self.dimension = dimension;
+
+        let my_entity_id = self.my_entity_id()?;
+        self.entities.retain(|entity_id, _| *entity_id == my_entity_id);
+        for player in self.players.values_mut() {
+            if player.entity_id != Some(my_entity_id) {
+                player.entity_id = None;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn dimension(&self) -> DimensionId {
+        self.dimension
     }
 
     pub fn unload_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
         let addr = ChunkAddr::new(chunk_x, chunk_z);
-        self.chunks.remove(&addr);
+        if let Some(chunks) = self.worlds.get_mut(&self.dimension) {
+            chunks.remove(&addr);
+        }
     }
 
     pub fn player_names(&self) -> Vec<&str> {
@@ -183,253 +298,106 @@ impl GameState {
             .collect()
     }
 
-    pub fn block_state_at(&self, position: &BlockPosition) -> Option<BlockState> {
-        let chunk = self.chunks.get(&position.chunk())?;
-        Some(chunk.block_state(position.local()))
+    pub fn hostile_positions(&self) -> Vec<Position> {
+        self.entities.values()
+            .filter(|entity| entity.is_hostile())
+            .map(|entity| entity.position)
+            .collect()
     }
 
-    pub fn find_block_ids_within(&self, block_id: u16, position: &BlockPosition, distance: i32) -> Vec<BlockPosition> {
-        let mut min_pos = position.clone();
-        min_pos.add_x(-distance);
-        min_pos.add_y(-distance);
-        min_pos.add_z(-distance);
-
-        let mut max_pos = position.clone();
-        max_pos.add_x(distance);
-        max_pos.add_y(distance);
-        max_pos.add_z(distance);
-
-        let min_chunk = min_pos.chunk();
-        let max_chunk = max_pos.chunk();
-        let mut result = Vec::default();
-
-        for chunk_x in min_chunk.x() .. (max_chunk.x() + 1) {
-            for chunk_z in min_chunk.z() .. (max_chunk.z() + 1) {
-                let chunk_addr = ChunkAddr::new(chunk_x, chunk_z);
-                if let Some(chunk) = self.chunks.get(&chunk_addr) {
-                    let matches = chunk.find_matching_block_state(|bs| bs.id() == block_id);
-                    result.extend(
-                        matches.into_iter()
-                            .map(|pos| BlockPosition::from_parts(chunk_addr, pos))
-                            .filter(|pos| pos.x() >= min_pos.x() &&
-                                pos.y() >= min_pos.y() &&
-                                pos.z() >= min_pos.z() &&
-                                pos.x() <= max_pos.x() &&
-                                pos.y() <= max_pos.y() &&
-                                pos.z() <= max_pos.z())
-                    );
-                }
-            }
-        }
-
-        result.sort_unstable_by(|pos1, pos2| pos1.distance_to_ord(&position).partial_cmp(&pos2.distance_to_ord(&position)).unwrap());
-        result
+    pub fn last_block_changes(&self) -> &[BlockPosition] {
+        &self.block_changes
     }
 
-    pub fn find_path_to(&self, start: BlockPosition, dest: BlockPosition) -> Option<Vec<BlockPosition>> {
-        astar(&start, 
-            |pos| self.find_walkable_positions(pos),
-            |pos| (pos.distance_to(&dest) as u64) + 1,
-            |pos| pos.distance_to(&dest) < 2.0)
-            .map(|(r, _)| r)
+    pub fn is_chunk_loaded(&self, addr: ChunkAddr) -> bool {
+        self.chunk(addr).is_some()
     }
 
-    fn find_walkable_positions(&self, pos: &BlockPosition) -> Vec<(BlockPosition, u64)> {
-        let mut result = Vec::default();
-        let is_passable = |x, y, z|
-            self.block_state_at(&pos.with_diff(x, y, z)).map_or(false, |bs| bs.is_passable());
-
-        let mut check_direction = |x, z| {
-            if is_passable(x, 1, z) {
-                if is_passable(x, 0, z) {
-                    if !is_passable(x, -1, z) {
-                        result.push((pos.with_diff(x, 0, z), 1));
-                    } else if !is_passable(x, -2, z) {
-                        result.push((pos.with_diff(x, -1, z), 2));
-                    }
-                } else if is_passable(x, 2, z) {
-                        result.push((pos.with_diff(x, 1, z), 2));
-                }
-            }
-        };
-        
-        check_direction(-1, 0);
-        check_direction(0, -1);
-        check_direction(1, 0);
-        check_direction(0, 1);
-        result
+    fn chunk(&self, addr: ChunkAddr) -> Option<&Chunk> {
+        self.worlds.get(&self.dimension)?.get(&addr)
     }
 
-    pub fn set_block_state(&mut self, pos: &BlockPosition, state: BlockState) {
-        let addr = pos.chunk();
-        if let Some(chunk) = self.chunks.get_mut(&addr) {
-            chunk.set_block_state(pos.local(), state);
-        } else {
-            warn!("Block update received for unloaded chunk ({}, {})", addr.x(), addr.z());
+    // Nothing exists above or below the 256 block tall world
+    pub fn block_state_at(&self, position: &BlockPosition) -> Option<BlockState> {
+        if position.y() < 0 || position.y() > 255 {
+            return None;
         }
+        let chunk = self.chunk(position.chunk())?;
+        Some(chunk.block_state(position.local()))
     }
-}
 
-fn load_single_chunk(chunk: &mut Chunk, y_offset: u8, data: &mut Buf) {
-    let bits_per_block = data.get_u8();
-    let palette: Option<Vec<u16>> = if bits_per_block <= 8 {
-        let palette_len = read_varint(data);
-        let mut v = Vec::new();
-        for _ in 0..palette_len {
-            v.push(read_varint(data) as u16);
+    // First block whose collision box the ray hits, stopping at unloaded chunks
+    pub fn raycast(&self, ray: &Ray, max_distance: Distance) -> Option<RayHit> {
+        for block in ray.voxels(max_distance) {
+            let collision_box = match self.block_state_at(&block)?.collision_box() {
+                Some(collision_box) => collision_box.offset(block.x() as f64, block.y() as f64, block.z() as f64),
+                None => continue
+            };
+            if let Some((distance, face)) = collision_box.ray_intersection(ray) {
+                if distance <= max_distance {
+                    return Some(RayHit {
+                        block,
+                        face,
+                        point: ray.point_at(distance),
+                        distance
+                    });
+                }
+            }
         }
-        Some(v)
-    } else {
         None
-    };
-
-    read_varint(data);
-
-    let starting_idx: u16 = CHUNK_WIDTH as u16 * CHUNK_WIDTH as u16 * y_offset as u16;
-    let mut buf: u128 = 0;
-    let mut remaining: u8 = 0;
-    for addr in 0..4096 {
-        if remaining < bits_per_block {
-            let temp = data.get_u64_be() as u128;
-            buf |= temp << remaining;
-            remaining += 64;
-        }
-
-        let temp_id: u16 = (buf & (0xFFFF >> (16 - bits_per_block as u16))) as u16;
-        let block_id = BlockState(palette.as_ref().map_or(temp_id, |p| p[temp_id as usize]));
-        chunk.set_block_state(LocalAddr(addr + starting_idx), block_id);
-        buf >>= bits_per_block;
-        remaining -= bits_per_block;
-    }
-
-    for addr in 0..2048 {
-        let temp = data.get_u8();
-        chunk.set_light_level(LocalAddr(2 * addr + starting_idx), temp & 0x0F);
-        chunk.set_light_level(LocalAddr(2 * addr + starting_idx + 1), temp >> 4);
     }
 
-    for addr in 0..2048 {
-        let temp = data.get_u8();
-        chunk.set_skylight_level(LocalAddr(2 * addr + starting_idx), temp & 0x0F);
-        chunk.set_skylight_level(LocalAddr(2 * addr + starting_idx + 1), temp >> 4);
+    pub fn has_line_of_sight(&self, from: &Position, to: &Position) -> bool {
+        let ray = Ray::towards(*from, to);
+        let distance = from.distance_to(to);
+        ray.voxels(distance).all(|block| self.is_chunk_loaded(block.chunk())) && self.raycast(&ray, distance).is_none()
     }
-}
 
-type EntityId = i32;
-
-struct PerBlock<T: Copy> {
-    data: Vec<T>,
-    default: T
-}
-
-impl <T: Copy> PerBlock<T> {
-    pub fn new(default: T) -> PerBlock<T> {
-        let mut res = PerBlock {
-            data: Vec::new(),
-            default
-        };
-        res.data.reserve(80 as usize * CHUNK_WIDTH as usize * CHUNK_WIDTH as usize);
-        res
-    }
-
-    pub fn get(&self, LocalAddr(addr): LocalAddr) -> T {
-        self.data.get(addr as usize).map_or(self.default, |val| *val)
-    }
-
-    pub fn set(&mut self, LocalAddr(addr): LocalAddr, val: T) {
-        let idx = addr as usize;
-        if self.data.len() <= idx {
-            let to_extend = idx - self.data.len() + 1;
-            self.data.extend(repeat(self.default).take(to_extend));
-        }
-        self.data[idx] = val;
-    }
-
-    pub fn iter(&self) -> Cloned<std::slice::Iter<T>> {
-        self.data.iter().cloned()
+    pub fn find_block_ids_within(&self, block_id: u16, position: &BlockPosition, distance: i32) -> Vec<BlockPosition> {
+        self.find_blocks_within(|bs| bs.id() == block_id, position, distance)
     }
 
-    /*pub fn trim(&mut self) {
-        let mut new_len = self.data.len();
-        while (new_len > 0 && self.data[new_len - 1] == self.default) {
-            new_len -= 1;
-        }
-        self.data.truncate(new_len);
-    }*/
-}
-
-pub struct Chunk {
-    block_states: PerBlock<BlockState>,
-    damage: PerBlock<u8>,
-    light: PerBlock<u8>,
-    skylight: PerBlock<u8>
-}
-
-impl Chunk {
-    pub fn new() -> Chunk {
-        Chunk {
-            block_states: PerBlock::new(BlockState(0)),
-            damage: PerBlock::new(0),
-            light: PerBlock::new(0),
-            skylight: PerBlock::new(15)
+    pub fn find_blocks_within(&self, pred: impl Fn(BlockState) -> bool, position: &BlockPosition, distance: i32) -> Vec<BlockPosition> {
+        let bounds = Cuboid::around(position, distance);
+        let mut result = Vec::default();
+        for chunk_addr in bounds.chunks() {
+            if let Some(chunk) = self.chunk(chunk_addr) {
+                let matches = chunk.find_matching_block_state(&pred, bounds.min().y(), bounds.max().y());
+                result.extend(
+                    matches.into_iter()
+                        .map(|pos| BlockPosition::from_parts(chunk_addr, pos))
+                        .filter(|pos| bounds.contains(pos))
+                );
+            }
         }
-    }
-
-    pub fn block_state(&self, addr: LocalAddr) -> BlockState {
-        self.block_states.get(addr)
-    }
-
-    pub fn set_block_state(&mut self, addr: LocalAddr, val: BlockState) {
-        self.block_states.set(addr, val);
-    }
-
-    pub fn damage(&self, addr: LocalAddr) -> u8 {
-        self.damage.get(addr)
-    }
-
-    pub fn set_damage(&mut self, addr: LocalAddr, val: u8) {
-        self.damage.set(addr, val);
-    }
 
-    pub fn light_level(&self, addr: LocalAddr) -> u8 {
-        self.light.get(addr)
-    }
-
-    pub fn set_light_level(&mut self, addr: LocalAddr, val: u8) {
-        self.light.set(addr, val);
-    }
-
-    pub fn skylight_level(&self, addr: LocalAddr) -> u8 {
-        self.skylight.get(addr)
+        result.sort_unstable_by(|pos1, pos2| pos1.distance_to_ord(&position).partial_cmp(&pos2.distance_to_ord(&position)).unwrap());
+        result
     }
 
-    pub fn set_skylight_level(&mut self, addr: LocalAddr, val: u8) {
-        self.skylight.set(addr, val);
+    pub fn find_path_to(&self, start: BlockPosition, dest: BlockPosition) -> Option<Path> {
+        self.find_path_with(start, &Near { pos: dest, radius: 2.0 }, &PathOptions::default())
     }
 
-    pub fn find_matching_block_state(&self, pred: impl Fn(BlockState) -> bool) -> Vec<LocalAddr> {
-        self.block_states.iter()
-            .enumerate()
-            .filter(|(_, bs)| pred(*bs))
-            .map(|(idx, _)| LocalAddr(idx as u16))
-            .collect()
+    pub fn find_path_with(&self, start: BlockPosition, goal: &dyn Goal, options: &PathOptions) -> Option<Path> {
+        path::find_path(self, start, goal, options)
     }
-}
 
-fn read_varint(buf: &mut Buf) -> i32 {
-    let mut result = 0;
-    let mut read = 0;
-    loop {
-        let byte = buf.get_u8();
-        result |= (byte as i32 & 0x7F) << (read * 7);
-
-        if byte & 0x80 == 0 {
-            return result;
+    pub fn set_block_state(&mut self, pos: &BlockPosition, state: BlockState) {
+        let addr = pos.chunk();
+        if pos.y() < 0 || pos.y() > 255 {
+            warn!("Block update received outside of the world at {:?}", pos);
+        } else if let Some(chunk) = self.worlds.get_mut(&self.dimension).and_then(|chunks| chunks.get_mut(&addr)) {
+            chunk.set_block_state(pos.local(), state);
+            self.block_changes.push(*pos);
+        } else {
+            warn!("Block update received for unloaded chunk ({}, {})", addr.x(), addr.z());
         }
-        read += 1;
     }
 }
 
+pub type EntityId = i32;
+
 struct Player {
     name: String,
     entity_id: Option<EntityId>
@@ -451,22 +419,207 @@ impl Player {
 pub fn entity_id(packet: &ServerPacket) -> Option<EntityId> {
         match *packet {
             ServerPacket::SpawnPlayer { entity_id, ..} => Some(entity_id),
+            ServerPacket::SpawnMob { entity_id, ..} => Some(entity_id),
+            ServerPacket::EntityRelativeMove { entity_id, ..} => Some(entity_id),
+            ServerPacket::EntityLookAndRelativeMove { entity_id, ..} => Some(entity_id),
+            ServerPacket::EntityTeleport { entity_id, ..} => Some(entity_id),
             _ => None
         }
 }
 
+// Mob type ids from SpawnMob that attack players on sight
+const HOSTILE_MOBS: [i32; 25] = [4, 5, 6, 23, 27, 34, 35, 36, 37, 50, 51, 52, 54, 55, 56, 59, 60, 61, 62, 63, 64, 66, 67, 68, 69];
+
 #[derive(Default)]
 struct Entity {
-    position: Position
+    position: Position,
+    rotation: Rotation,
+    mob_type: Option<i32>
 }
 
 impl Entity {
+    fn mob(mob_type: i32) -> Self {
+        Entity {
+            position: Position::default(),
+            rotation: Rotation::default(),
+            mob_type: Some(mob_type)
+        }
+    }
+
+    fn is_hostile(&self) -> bool {
+        self.mob_type.is_some_and(|mob_type| HOSTILE_MOBS.contains(&mob_type))
+    }
+
     pub fn handle_packet(&mut self, packet: &ServerPacket) {
         match *packet {
-            ServerPacket::SpawnPlayer { x, y, z, ..} => {
+            ServerPacket::SpawnPlayer { x, y, z, ..} |
+            ServerPacket::SpawnMob { x, y, z, ..} |
+            ServerPacket::EntityTeleport { x, y, z, ..} => {
                 self.position = Position::new(x, y, z);
             }
+            // Relative moves are in 1/4096 of a block
+            ServerPacket::EntityRelativeMove { delta_x, delta_y, delta_z, ..} |
+            ServerPacket::EntityLookAndRelativeMove { delta_x, delta_y, delta_z, ..} => {
+                self.position = self.position.with_diff(delta_x as f64 / 4096.0, delta_y as f64 / 4096.0, delta_z as f64 / 4096.0);
+            }
             _ => ()
         }
     }
-}
\ No newline at end of file
+}
+// Small synthetic worlds for tests elsewhere in the crate
+#[cfg(test)]
+impl GameState {
+    // A spawned player standing at `position`, with air in every chunk from
+    // (min_x, min_z) to (max_x, max_z)
+    pub(crate) fn with_chunks(position: Position, min_x: i32, min_z: i32, max_x: i32, max_z: i32) -> Self {
+        let mut gamestate = GameState::new(Uuid::nil(), String::from("bot"));
+        gamestate.players.get_mut(&Uuid::nil()).unwrap().entity_id = Some(1);
+        gamestate.entities.insert(1, Entity {
+            position,
+            ..Entity::default()
+        });
+        for x in min_x..=max_x {
+            for z in min_z..=max_z {
+                gamestate.load_chunk_data(x, z, 0, &Bytes::new()).unwrap();
+            }
+        }
+        gamestate
+    }
+
+    pub(crate) fn fill(&mut self, min: BlockPosition, max: BlockPosition, state: BlockState) {
+        for x in min.x()..=max.x() {
+            for y in min.y()..=max.y() {
+                for z in min.z()..=max.z() {
+                    self.set_block_state(&BlockPosition::new(x, y, z), state);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::BlockFace;
+    use nbt::DecodeError;
+
+    #[test]
+    fn on_ground_on_top_of_collision_boxes() {
+        let mut gamestate = GameState::with_chunks(Position::new(0.5, 65.0, 0.5), 0, 0, 0, 0);
+        let on_ground = |gamestate: &mut GameState, y: f64| {
+            gamestate.set_my_position(Position::new(0.5, y, 0.5)).unwrap();
+            gamestate.is_on_ground().unwrap()
+        };
+        assert!(!on_ground(&mut gamestate, 65.0));
+        gamestate.set_block_state(&BlockPosition::new(0, 64, 0), BlockState(1 << 4));
+        assert!(on_ground(&mut gamestate, 65.0));
+        assert!(!on_ground(&mut gamestate, 65.3));
+
+        gamestate.set_block_state(&BlockPosition::new(0, 64, 0), BlockState(44 << 4));
+        assert!(on_ground(&mut gamestate, 64.5));
+        assert!(!on_ground(&mut gamestate, 65.0));
+
+        // Fences are one and a half blocks tall
+        gamestate.set_block_state(&BlockPosition::new(0, 64, 0), BlockState(85 << 4));
+        assert!(on_ground(&mut gamestate, 65.5));
+        assert!(!on_ground(&mut gamestate, 65.0));
+
+        // Off the top of the loaded world
+        assert!(on_ground(&mut gamestate, 300.0));
+    }
+
+    #[test]
+    fn raycast_hits_collision_boxes() {
+        let mut gamestate = GameState::with_chunks(Position::new(0.5, 65.0, 0.5), 0, 0, 0, 0);
+        // A bottom slab two blocks ahead, with tall grass in front of it
+        gamestate.set_block_state(&BlockPosition::new(0, 64, 2), BlockState(44 << 4));
+        gamestate.set_block_state(&BlockPosition::new(0, 64, 1), BlockState(31 << 4 | 1));
+
+        let eyes = Position::new(0.5, 65.62, 0.5);
+        let hit = gamestate.raycast(&Ray::towards(eyes, &Position::new(0.5, 64.0, 3.0)), 4.5).unwrap();
+        assert_eq!(hit.block, BlockPosition::new(0, 64, 2));
+        assert_eq!(hit.face, BlockFace::Up);
+        assert!((hit.point.y() - 64.5).abs() < 1e-9);
+
+        // Over the slab, and through the side of it
+        let over = Position::new(0.5, 64.6, 2.5);
+        assert!(gamestate.raycast(&Ray::towards(eyes, &over), eyes.distance_to(&over)).is_none());
+        let hit = gamestate.raycast(&Ray::new(Position::new(0.5, 64.25, 0.5), (0.0, 0.0, 1.0)), 4.5).unwrap();
+        assert_eq!((hit.face, hit.distance), (BlockFace::North, 1.5));
+        // Out of reach
+        assert!(gamestate.raycast(&Ray::new(Position::new(0.5, 64.25, 0.5), (0.0, 0.0, 1.0)), 1.0).is_none());
+
+        assert!(gamestate.has_line_of_sight(&eyes, &Position::new(0.5, 65.0, 4.5)));
+        assert!(!gamestate.has_line_of_sight(&eyes, &Position::new(0.5, 64.2, 3.5)));
+        // Nothing is known about unloaded chunks
+        assert!(!gamestate.has_line_of_sight(&eyes, &Position::new(0.5, 65.0, 20.5)));
+    }
+
+    #[test]
+    fn truncated_chunks_are_not_loaded() {
+        let mut gamestate = GameState::with_chunks(Position::new(0.5, 65.0, 0.5), 0, 0, 0, 0);
+        // Claims a section, then ends in the middle of its palette
+        let packet = ServerPacket::ChunkData {
+            chunk_x: 1,
+            chunk_z: 0,
+            full_chunk: true,
+            primary_bitmask: 1,
+            data: Bytes::from(vec![4, 3, 0])
+        };
+        match gamestate.handle_packet(&packet) {
+            Err(Error::Decode(DecodeError::UnexpectedEnd(1, 0))) => (),
+            res => panic!("expected UnexpectedEnd, got {:?}", res)
+        }
+        assert!(!gamestate.is_chunk_loaded(ChunkAddr::new(1, 0)));
+        assert!(gamestate.is_chunk_loaded(ChunkAddr::new(0, 0)));
+    }
+
+    #[test]
+    fn no_blocks_outside_of_world_height() {
+        let mut gamestate = GameState::new(Uuid::nil(), String::from("bot"));
+        gamestate.worlds.entry(DimensionId::Overworld).or_default().insert(ChunkAddr::new(-1, 0), Chunk::new());
+        let stone = BlockState(1 << 4);
+        for y in &[0, 255] {
+            gamestate.set_block_state(&BlockPosition::new(-3, *y, 5), stone);
+            assert_eq!(gamestate.block_state_at(&BlockPosition::new(-3, *y, 5)), Some(stone));
+        }
+        for y in &[-1, 256, -256, 511] {
+            gamestate.set_block_state(&BlockPosition::new(-3, *y, 5), BlockState(0));
+            assert_eq!(gamestate.block_state_at(&BlockPosition::new(-3, *y, 5)), None);
+        }
+        assert_eq!(gamestate.block_state_at(&BlockPosition::new(-3, 0, 5)), Some(stone));
+        assert_eq!(gamestate.block_state_at(&BlockPosition::new(-3, 255, 5)), Some(stone));
+        assert_eq!(gamestate.block_state_at(&BlockPosition::new(3, 0, 5)), None);
+    }
+
+    #[test]
+    fn chunks_are_dropped_on_leaving_a_dimension() {
+        let mut gamestate = GameState::new(Uuid::nil(), String::from("bot"));
+        gamestate.players.get_mut(&Uuid::nil()).unwrap().entity_id = Some(1);
+        let stone = BlockState(1 << 4);
+        let netherrack = BlockState(87 << 4);
+        let pos = BlockPosition::new(8, 64, 8);
+
+        gamestate.load_chunk_data(0, 0, 0, &Bytes::new()).unwrap();
+        gamestate.set_block_state(&pos, stone);
+        gamestate.change_dimension(DimensionId::Nether).unwrap();
+        assert!(!gamestate.is_chunk_loaded(ChunkAddr::new(0, 0)));
+        assert_eq!(gamestate.block_state_at(&pos), None);
+
+        gamestate.load_chunk_data(0, 0, 0, &Bytes::new()).unwrap();
+        gamestate.set_block_state(&pos, netherrack);
+        assert_eq!(gamestate.block_state_at(&pos), Some(netherrack));
+
+        // Nothing stale is left of the overworld on returning
+        gamestate.change_dimension(DimensionId::Overworld).unwrap();
+        assert!(!gamestate.is_chunk_loaded(ChunkAddr::new(0, 0)));
+        assert_eq!(gamestate.block_state_at(&pos), None);
+        assert!(!gamestate.worlds.contains_key(&DimensionId::Nether));
+
+        // Nor after respawning in the same dimension
+        gamestate.load_chunk_data(0, 0, 0, &Bytes::new()).unwrap();
+        gamestate.set_block_state(&pos, stone);
+        gamestate.change_dimension(DimensionId::Overworld).unwrap();
+        assert_eq!(gamestate.block_state_at(&pos), None);
+    }
+}
@@ -75,7 +75,7 @@ impl Position {
     }
 
     pub fn block_position(&self) -> BlockPosition {
-        BlockPosition::new(self.x as i32, self.y as i32, self.z as i32)
+        BlockPosition::new(self.x.floor() as i32, self.y.floor() as i32, self.z.floor() as i32)
     }
 
     pub fn distance_to_ord(&self, other: &Position) -> Distance {
@@ -102,6 +102,16 @@ impl BlockPosition {
         BlockPosition { x, y, z }
     }
 
+    // x and z take 26 bits each and y 12, all two's complement
+    pub fn from_packed(val: u64) -> Self {
+        let val = val as i64;
+        Self::new((val >> 38) as i32, (val << 26 >> 52) as i32, (val << 38 >> 38) as i32)
+    }
+
+    pub fn to_packed(&self) -> u64 {
+        ((self.x as u64 & 0x3FFFFFF) << 38) | ((self.y as u64 & 0xFFF) << 26) | (self.z as u64 & 0x3FFFFFF)
+    }
+
     pub fn from_parts(chunk: ChunkAddr, local: LocalAddr) -> Self {
         Self::new(chunk.x() as i32 * CHUNK_WIDTH as i32 + local.x() as i32,
             local.y() as i32,
@@ -185,6 +195,345 @@ impl BlockPosition {
     pub fn distance_to(&self, other: &BlockPosition) -> Distance {
         (self.distance_to_ord(other) as f64).sqrt()
     }
+
+    pub fn offset(&self, face: BlockFace) -> BlockPosition {
+        let (x, y, z) = face.offset();
+        self.with_diff(x, y, z)
+    }
+
+    // Every position within `radius` of this one
+    pub fn sphere(&self, radius: i32) -> impl Iterator<Item=BlockPosition> {
+        let center = *self;
+        Cuboid::around(self, radius).iter()
+            .filter(move |pos| pos.distance_to_ord(&center) <= radius * radius)
+    }
+
+    // Positions further than `radius - 1` but within `radius`, so that the
+    // shells for 0..=n cover the sphere of radius n exactly once
+    pub fn shell(&self, radius: i32) -> impl Iterator<Item=BlockPosition> {
+        let center = *self;
+        let inner = if radius > 0 { (radius - 1) * (radius - 1) } else { -1 };
+        Cuboid::around(self, radius).iter()
+            .filter(move |pos| {
+                let distance = pos.distance_to_ord(&center);
+                distance > inner && distance <= radius * radius
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockFace {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East
+}
+
+impl BlockFace {
+    pub const ALL: [BlockFace; 6] = [BlockFace::Down, BlockFace::Up, BlockFace::North, BlockFace::South, BlockFace::West, BlockFace::East];
+
+    pub fn offset(self) -> (i32, i32, i32) {
+        match self {
+            BlockFace::Down => (0, -1, 0),
+            BlockFace::Up => (0, 1, 0),
+            BlockFace::North => (0, 0, -1),
+            BlockFace::South => (0, 0, 1),
+            BlockFace::West => (-1, 0, 0),
+            BlockFace::East => (1, 0, 0)
+        }
+    }
+
+    pub fn opposite(self) -> BlockFace {
+        match self {
+            BlockFace::Down => BlockFace::Up,
+            BlockFace::Up => BlockFace::Down,
+            BlockFace::North => BlockFace::South,
+            BlockFace::South => BlockFace::North,
+            BlockFace::West => BlockFace::East,
+            BlockFace::East => BlockFace::West
+        }
+    }
+
+    // Face number used by the digging and placement packets
+    pub fn id(self) -> i32 {
+        match self {
+            BlockFace::Down => 0,
+            BlockFace::Up => 1,
+            BlockFace::North => 2,
+            BlockFace::South => 3,
+            BlockFace::West => 4,
+            BlockFace::East => 5
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    South,
+    West,
+    East
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::South, Direction::West, Direction::East];
+
+    pub fn face(self) -> BlockFace {
+        match self {
+            Direction::North => BlockFace::North,
+            Direction::South => BlockFace::South,
+            Direction::West => BlockFace::West,
+            Direction::East => BlockFace::East
+        }
+    }
+
+    pub fn offset(self) -> (i32, i32) {
+        let (x, _, z) = self.face().offset();
+        (x, z)
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+            Direction::East => Direction::West
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    min: Position,
+    max: Position
+}
+
+impl Aabb {
+    pub fn new(min: Position, max: Position) -> Self {
+        Aabb {
+            min: Position::new(min.x.min(max.x), min.y.min(max.y), min.z.min(max.z)),
+            max: Position::new(min.x.max(max.x), min.y.max(max.y), min.z.max(max.z))
+        }
+    }
+
+    pub fn from_parts(x1: Distance, y1: Distance, z1: Distance, x2: Distance, y2: Distance, z2: Distance) -> Self {
+        Aabb::new(Position::new(x1, y1, z1), Position::new(x2, y2, z2))
+    }
+
+    pub fn block(pos: &BlockPosition) -> Self {
+        let min = Position::new(pos.x as Distance, pos.y as Distance, pos.z as Distance);
+        Aabb::new(min, min.with_diff(1.0, 1.0, 1.0))
+    }
+
+    pub fn min(&self) -> &Position {
+        &self.min
+    }
+
+    pub fn max(&self) -> &Position {
+        &self.max
+    }
+
+    pub fn offset(&self, x: Distance, y: Distance, z: Distance) -> Aabb {
+        Aabb {
+            min: self.min.with_diff(x, y, z),
+            max: self.max.with_diff(x, y, z)
+        }
+    }
+
+    // Stretches the box along a motion vector, covering everything it passes
+    pub fn expand(&self, x: Distance, y: Distance, z: Distance) -> Aabb {
+        Aabb::new(self.min.with_diff(x.min(0.0), y.min(0.0), z.min(0.0)),
+            self.max.with_diff(x.max(0.0), y.max(0.0), z.max(0.0)))
+    }
+
+    pub fn grow(&self, amount: Distance) -> Aabb {
+        Aabb::new(self.min.with_diff(-amount, -amount, -amount), self.max.with_diff(amount, amount, amount))
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x < other.max.x && self.max.x > other.min.x &&
+            self.min.y < other.max.y && self.max.y > other.min.y &&
+            self.min.z < other.max.z && self.max.z > other.min.z
+    }
+
+    pub fn intersection(&self, other: &Aabb) -> Option<Aabb> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Aabb::from_parts(self.min.x.max(other.min.x), self.min.y.max(other.min.y), self.min.z.max(other.min.z),
+            self.max.x.min(other.max.x), self.max.y.min(other.max.y), self.max.z.min(other.max.z)))
+    }
+
+    pub fn contains(&self, pos: &Position) -> bool {
+        pos.x >= self.min.x && pos.x < self.max.x &&
+            pos.y >= self.min.y && pos.y < self.max.y &&
+            pos.z >= self.min.z && pos.z < self.max.z
+    }
+
+    // Distance along the ray to where it enters this box, with the face it
+    // enters through. Rays starting inside the box don't hit it.
+    pub fn ray_intersection(&self, ray: &Ray) -> Option<(Distance, BlockFace)> {
+        let axes = [
+            (ray.origin.x, ray.direction.0, self.min.x, self.max.x, BlockFace::West, BlockFace::East),
+            (ray.origin.y, ray.direction.1, self.min.y, self.max.y, BlockFace::Down, BlockFace::Up),
+            (ray.origin.z, ray.direction.2, self.min.z, self.max.z, BlockFace::North, BlockFace::South)
+        ];
+
+        let mut entry = Distance::NEG_INFINITY;
+        let mut exit = Distance::INFINITY;
+        let mut face = BlockFace::Up;
+        for (origin, delta, min, max, min_face, max_face) in axes.iter().cloned() {
+            if delta == 0.0 {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let (near, far, near_face) = if delta > 0.0 {
+                ((min - origin) / delta, (max - origin) / delta, min_face)
+            } else {
+                ((max - origin) / delta, (min - origin) / delta, max_face)
+            };
+            if near > entry {
+                entry = near;
+                face = near_face;
+            }
+            exit = exit.min(far);
+        }
+
+        if entry >= 0.0 && entry <= exit {
+            Some((entry, face))
+        } else {
+            None
+        }
+    }
+
+    // Moves this box by `motion` and returns the fraction of the motion
+    // completed before it hits `other`, with the face of `other` it hit
+    pub fn sweep(&self, other: &Aabb, motion: (Distance, Distance, Distance)) -> Option<(Distance, BlockFace)> {
+        let (dx, dy, dz) = motion;
+        let axes = [
+            (self.min.x, self.max.x, other.min.x, other.max.x, dx, BlockFace::West, BlockFace::East),
+            (self.min.y, self.max.y, other.min.y, other.max.y, dy, BlockFace::Down, BlockFace::Up),
+            (self.min.z, self.max.z, other.min.z, other.max.z, dz, BlockFace::North, BlockFace::South)
+        ];
+
+        let mut entry: Distance = 0.0;
+        let mut exit: Distance = 1.0;
+        let mut face = None;
+        for (min, max, other_min, other_max, delta, near_face, far_face) in axes.iter().cloned() {
+            if delta == 0.0 {
+                if max <= other_min || min >= other_max {
+                    return None;
+                }
+                continue;
+            }
+
+            let (enter_at, leave_at, hit_face) = if delta > 0.0 {
+                ((other_min - max) / delta, (other_max - min) / delta, near_face)
+            } else {
+                ((other_max - min) / delta, (other_min - max) / delta, far_face)
+            };
+            if enter_at > entry || face.is_none() && enter_at >= entry {
+                entry = enter_at.max(entry);
+                face = Some(hit_face);
+            }
+            exit = exit.min(leave_at);
+        }
+
+        match face {
+            Some(face) if entry < exit && (0.0..=1.0).contains(&entry) => Some((entry, face)),
+            _ => None
+        }
+    }
+}
+
+pub trait Region {
+    fn contains(&self, pos: &BlockPosition) -> bool;
+
+    fn bounds(&self) -> Cuboid;
+}
+
+// All block positions between two corners, both inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    min: BlockPosition,
+    max: BlockPosition
+}
+
+impl Cuboid {
+    pub fn new(a: BlockPosition, b: BlockPosition) -> Self {
+        Cuboid {
+            min: BlockPosition::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: BlockPosition::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+        }
+    }
+
+    pub fn around(center: &BlockPosition, radius: i32) -> Self {
+        Cuboid::new(center.with_diff(-radius, -radius, -radius), center.with_diff(radius, radius, radius))
+    }
+
+    pub fn min(&self) -> &BlockPosition {
+        &self.min
+    }
+
+    pub fn max(&self) -> &BlockPosition {
+        &self.max
+    }
+
+    pub fn volume(&self) -> u64 {
+        (self.max.x - self.min.x + 1) as u64 * (self.max.y - self.min.y + 1) as u64 * (self.max.z - self.min.z + 1) as u64
+    }
+
+    pub fn intersects(&self, other: &Cuboid) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x &&
+            self.min.y <= other.max.y && self.max.y >= other.min.y &&
+            self.min.z <= other.max.z && self.max.z >= other.min.z
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item=ChunkAddr> {
+        let (min, max) = (self.min.chunk(), self.max.chunk());
+        (min.x ..= max.x).flat_map(move |x| (min.z ..= max.z).map(move |z| ChunkAddr::new(x, z)))
+    }
+
+    // Iterates x fastest, then z, then y
+    pub fn iter(&self) -> impl Iterator<Item=BlockPosition> {
+        let (min, max) = (self.min, self.max);
+        (min.y ..= max.y).flat_map(move |y|
+            (min.z ..= max.z).flat_map(move |z|
+                (min.x ..= max.x).map(move |x| BlockPosition::new(x, y, z))))
+    }
+}
+
+impl Region for Cuboid {
+    fn contains(&self, pos: &BlockPosition) -> bool {
+        pos.x >= self.min.x && pos.x <= self.max.x &&
+            pos.y >= self.min.y && pos.y <= self.max.y &&
+            pos.z >= self.min.z && pos.z <= self.max.z
+    }
+
+    fn bounds(&self) -> Cuboid {
+        *self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    pub center: BlockPosition,
+    pub radius: i32
+}
+
+impl Region for Sphere {
+    fn contains(&self, pos: &BlockPosition) -> bool {
+        pos.distance_to_ord(&self.center) <= self.radius * self.radius
+    }
+
+    fn bounds(&self) -> Cuboid {
+        Cuboid::around(&self.center, self.radius)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -221,6 +570,12 @@ impl Rotation {
     pub fn add_pitch(&mut self, pitch: Angle) {
         self.pitch += pitch;
     }
+
+    // Unit vector the rotation faces, yaw 0 looks south and pitch 90 straight down
+    pub fn direction(self) -> (Distance, Distance, Distance) {
+        let (yaw, pitch) = ((self.yaw as Distance).to_radians(), (self.pitch as Distance).to_radians());
+        (-yaw.sin() * pitch.cos(), -pitch.sin(), yaw.cos() * pitch.cos())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -359,4 +714,387 @@ impl ChunkAddr {
     pub fn z(self) -> i32 {
         self.z
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    origin: Position,
+    direction: (Distance, Distance, Distance)
+}
+
+impl Ray {
+    pub fn new(origin: Position, direction: (Distance, Distance, Distance)) -> Self {
+        let (x, y, z) = direction;
+        let length = (x * x + y * y + z * z).sqrt();
+        Ray {
+            origin,
+            direction: (x / length, y / length, z / length)
+        }
+    }
+
+    pub fn from_rotation(origin: Position, rotation: Rotation) -> Self {
+        Ray::new(origin, rotation.direction())
+    }
+
+    pub fn towards(origin: Position, target: &Position) -> Self {
+        Ray::new(origin, (target.x - origin.x, target.y - origin.y, target.z - origin.z))
+    }
+
+    pub fn origin(&self) -> &Position {
+        &self.origin
+    }
+
+    pub fn direction(&self) -> (Distance, Distance, Distance) {
+        self.direction
+    }
+
+    pub fn point_at(&self, distance: Distance) -> Position {
+        self.origin.with_diff(self.direction.0 * distance, self.direction.1 * distance, self.direction.2 * distance)
+    }
+
+    // Every block the ray passes through, in order, up to `max_distance`
+    pub fn voxels(&self, max_distance: Distance) -> Voxels {
+        let axis = |origin: Distance, delta: Distance| {
+            let block = origin.floor();
+            if delta > 0.0 {
+                (1, (block + 1.0 - origin) / delta, 1.0 / delta)
+            } else if delta < 0.0 {
+                (-1, (block - origin) / delta, -1.0 / delta)
+            } else {
+                (0, Distance::INFINITY, Distance::INFINITY)
+            }
+        };
+        let (step_x, next_x, delta_x) = axis(self.origin.x, self.direction.0);
+        let (step_y, next_y, delta_y) = axis(self.origin.y, self.direction.1);
+        let (step_z, next_z, delta_z) = axis(self.origin.z, self.direction.2);
+
+        Voxels {
+            current: Some(self.origin.block_position()),
+            step: (step_x, step_y, step_z),
+            next: (next_x, next_y, next_z),
+            delta: (delta_x, delta_y, delta_z),
+            max_distance
+        }
+    }
+}
+
+// Amanatides & Woo voxel traversal
+pub struct Voxels {
+    current: Option<BlockPosition>,
+    step: (i32, i32, i32),
+    // Distance along the ray to the next boundary on each axis
+    next: (Distance, Distance, Distance),
+    delta: (Distance, Distance, Distance),
+    max_distance: Distance
+}
+
+impl Iterator for Voxels {
+    type Item = BlockPosition;
+
+    fn next(&mut self) -> Option<BlockPosition> {
+        let current = self.current?;
+        let (next_x, next_y, next_z) = self.next;
+        let distance = next_x.min(next_y).min(next_z);
+        self.current = if distance > self.max_distance {
+            None
+        } else if distance == next_x {
+            self.next.0 += self.delta.0;
+            Some(current.with_diff(self.step.0, 0, 0))
+        } else if distance == next_y {
+            self.next.1 += self.delta.1;
+            Some(current.with_diff(0, self.step.1, 0))
+        } else {
+            self.next.2 += self.delta.2;
+            Some(current.with_diff(0, 0, self.step.2))
+        };
+        Some(current)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub block: BlockPosition,
+    pub face: BlockFace,
+    pub point: Position,
+    pub distance: Distance
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_XZ: i32 = (1 << 25) - 1;
+    const MIN_XZ: i32 = -(1 << 25);
+
+    // Boundary values of x and z, with their negations
+    fn edges() -> Vec<i32> {
+        let mut values = vec![0, MIN_XZ];
+        for v in &[1, 15, 16, 17, 31, 32, 255, 256, 4096, MAX_XZ - 16, MAX_XZ] {
+            values.push(*v);
+            values.push(-*v);
+        }
+        values
+    }
+
+    // Deterministic pseudo-random values spread over the whole range
+    fn spread(count: usize, min: i32, max: i32) -> Vec<i32> {
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        (0..count).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            min + (state % (max as i64 - min as i64 + 1) as u64) as i32
+        }).collect()
+    }
+
+    fn samples() -> Vec<BlockPosition> {
+        let mut result = Vec::new();
+        for x in edges() {
+            for z in edges() {
+                for y in &[0, 1, 15, 16, 254, 255] {
+                    result.push(BlockPosition::new(x, *y, z));
+                }
+            }
+        }
+        let xs = spread(2000, MIN_XZ, MAX_XZ);
+        let ys = spread(2000, 0, 255);
+        let zs = spread(2000, MIN_XZ, MAX_XZ);
+        for i in 0..2000 {
+            result.push(BlockPosition::new(xs[i], ys[(i * 7) % 2000], zs[(i * 13) % 2000]));
+        }
+        result
+    }
+
+    #[test]
+    fn packed_round_trip() {
+        for pos in samples() {
+            assert_eq!(BlockPosition::from_packed(pos.to_packed()), pos, "{:?}", pos);
+        }
+        for y in &[-2048, -1, 256, 2047] {
+            let pos = BlockPosition::new(-5, *y, 5);
+            assert_eq!(BlockPosition::from_packed(pos.to_packed()), pos);
+        }
+    }
+
+    #[test]
+    fn packed_layout() {
+        assert_eq!(BlockPosition::new(-1, 0, 0).to_packed(), 0xFFFF_FFC0_0000_0000);
+        assert_eq!(BlockPosition::new(0, 0, -1).to_packed(), 0x0000_0000_03FF_FFFF);
+        assert_eq!(BlockPosition::new(0, 255, 0).to_packed(), 0xFF << 26);
+        assert_eq!(BlockPosition::new(1, 2, 3).to_packed(), (1 << 38) | (2 << 26) | 3);
+        assert_eq!(BlockPosition::from_packed(0xFFFF_FFFF_FFFF_FFFF), BlockPosition::new(-1, -1, -1));
+    }
+
+    #[test]
+    fn chunk_and_local_round_trip() {
+        for pos in samples() {
+            let chunk = pos.chunk();
+            let local = pos.local();
+            assert_eq!(BlockPosition::from_parts(chunk, local), pos, "{:?}", pos);
+            assert!(local.x() < CHUNK_WIDTH && local.z() < CHUNK_WIDTH);
+            assert_eq!(local.y() as i32, pos.y());
+            assert!(chunk.x() * 16 <= pos.x() && pos.x() < chunk.x() * 16 + 16, "{:?}", pos);
+            assert!(chunk.z() * 16 <= pos.z() && pos.z() < chunk.z() * 16 + 16, "{:?}", pos);
+        }
+    }
+
+    #[test]
+    fn chunks_round_toward_negative_infinity() {
+        let cases = [(-17, -2, 15), (-16, -1, 0), (-1, -1, 15), (0, 0, 0), (15, 0, 15), (16, 1, 0), (MIN_XZ, MIN_XZ / 16, 0)];
+        for &(v, chunk, local) in cases.iter() {
+            let pos = BlockPosition::new(v, 64, v);
+            assert_eq!(pos.chunk(), ChunkAddr::new(chunk, chunk), "{}", v);
+            assert_eq!((pos.local().x(), pos.local().z()), (local, local), "{}", v);
+        }
+    }
+
+    #[test]
+    fn block_position_floors_every_octant() {
+        for &sx in &[-1.0, 1.0] {
+            for &sy in &[-1.0, 1.0] {
+                for &sz in &[-1.0, 1.0] {
+                    for &v in &[0.25, 0.5, 1.0, 15.75, 16.0, 16.5] {
+                        let pos = Position::new(sx * v, 64.0 + sy * v, sz * v);
+                        let block = pos.block_position();
+                        assert_eq!(block, BlockPosition::new((sx * v).floor() as i32,
+                            (64.0 + sy * v).floor() as i32, (sz * v).floor() as i32));
+                        assert!(block.x() as f64 <= pos.x() && pos.x() < block.x() as f64 + 1.0);
+                        assert!(block.y() as f64 <= pos.y() && pos.y() < block.y() as f64 + 1.0);
+                        assert!(block.z() as f64 <= pos.z() && pos.z() < block.z() as f64 + 1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    fn unit() -> Aabb {
+        Aabb::from_parts(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)
+    }
+
+    #[test]
+    fn aabb_overlap_is_strict() {
+        let a = unit();
+        assert_eq!(Aabb::from_parts(1.0, 1.0, 1.0, 0.0, 0.0, 0.0), a);
+        assert!(a.intersects(&a.offset(0.5, 0.5, 0.5)));
+        // Touching faces or edges don't overlap
+        assert!(!a.intersects(&a.offset(1.0, 0.0, 0.0)));
+        assert!(!a.intersects(&a.offset(-1.0, 1.0, 0.0)));
+        assert_eq!(a.intersection(&a.offset(0.5, 0.0, -0.5)), Some(Aabb::from_parts(0.5, 0.0, 0.0, 1.0, 1.0, 0.5)));
+        assert_eq!(a.intersection(&a.offset(0.0, 1.0, 0.0)), None);
+
+        assert!(a.contains(&Position::new(0.0, 0.0, 0.0)));
+        assert!(a.contains(&Position::new(0.5, 0.999, 0.5)));
+        assert!(!a.contains(&Position::new(0.5, 1.0, 0.5)));
+        assert!(!a.contains(&Position::new(-0.001, 0.5, 0.5)));
+
+        assert_eq!(a.expand(-2.0, 0.0, 3.0), Aabb::from_parts(-2.0, 0.0, 0.0, 1.0, 1.0, 4.0));
+        assert_eq!(a.grow(0.5), Aabb::from_parts(-0.5, -0.5, -0.5, 1.5, 1.5, 1.5));
+        assert_eq!(Aabb::block(&BlockPosition::new(-3, 5, 7)), Aabb::from_parts(-3.0, 5.0, 7.0, -2.0, 6.0, 8.0));
+    }
+
+    #[test]
+    fn sweep_stops_at_the_first_face() {
+        let player = Aabb::from_parts(0.0, 0.0, 0.0, 0.6, 1.8, 0.6);
+        let wall = Aabb::from_parts(2.0, 0.0, -1.0, 3.0, 1.0, 2.0);
+        let (fraction, face) = player.sweep(&wall, (2.8, 0.0, 0.0)).unwrap();
+        assert!((fraction - 0.5).abs() < 1e-9);
+        assert_eq!(face, BlockFace::West);
+        // The same from the other side
+        let (fraction, face) = player.offset(3.4, 0.0, 0.0).sweep(&wall, (-0.8, 0.0, 0.0)).unwrap();
+        assert!((fraction - 0.5).abs() < 1e-9);
+        assert_eq!(face, BlockFace::East);
+
+        // Falling onto the top
+        let (fraction, face) = player.offset(2.0, 2.0, 0.0).sweep(&wall, (0.0, -4.0, 0.0)).unwrap();
+        assert!((fraction - 0.25).abs() < 1e-9);
+        assert_eq!(face, BlockFace::Up);
+
+        // Already touching and moving into it
+        assert_eq!(player.offset(1.4, 0.0, 0.0).sweep(&wall, (1.0, 0.0, 0.0)), Some((0.0, BlockFace::West)));
+
+        // Too short, moving away, passing beside it or not moving at all
+        assert_eq!(player.sweep(&wall, (1.0, 0.0, 0.0)), None);
+        assert_eq!(player.sweep(&wall, (-1.0, 0.0, 0.0)), None);
+        assert_eq!(player.offset(0.0, 0.0, 2.0).sweep(&wall, (4.0, 0.0, 0.0)), None);
+        assert_eq!(player.sweep(&wall, (0.0, 0.0, 0.0)), None);
+        assert_eq!(player.offset(1.4, 0.0, 0.0).sweep(&wall, (0.0, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn cuboids() {
+        let cuboid = Cuboid::new(BlockPosition::new(1, 5, -1), BlockPosition::new(-1, 4, 0));
+        assert_eq!(*cuboid.min(), BlockPosition::new(-1, 4, -1));
+        assert_eq!(*cuboid.max(), BlockPosition::new(1, 5, 0));
+        assert_eq!(cuboid.volume(), 12);
+        assert_eq!(cuboid.iter().count(), 12);
+        assert!(cuboid.iter().all(|pos| cuboid.contains(&pos)));
+        assert_eq!(cuboid.iter().take(4).collect::<Vec<_>>(), vec![BlockPosition::new(-1, 4, -1),
+            BlockPosition::new(0, 4, -1), BlockPosition::new(1, 4, -1), BlockPosition::new(-1, 4, 0)]);
+        assert!(!cuboid.contains(&BlockPosition::new(2, 4, 0)));
+        assert!(!cuboid.contains(&BlockPosition::new(0, 6, 0)));
+
+        let single = Cuboid::new(BlockPosition::new(1, 5, 0), BlockPosition::new(1, 5, 0));
+        assert_eq!(single.volume(), 1);
+        assert!(cuboid.intersects(&single));
+        assert!(!cuboid.intersects(&Cuboid::new(BlockPosition::new(2, 5, 0), BlockPosition::new(3, 5, 0))));
+        assert_eq!(cuboid.bounds(), cuboid);
+
+        // Across the chunk boundary at zero
+        let chunks: Vec<ChunkAddr> = Cuboid::new(BlockPosition::new(-1, 0, 15), BlockPosition::new(16, 0, 16)).chunks().collect();
+        assert_eq!(chunks, vec![ChunkAddr::new(-1, 0), ChunkAddr::new(-1, 1), ChunkAddr::new(0, 0),
+            ChunkAddr::new(0, 1), ChunkAddr::new(1, 0), ChunkAddr::new(1, 1)]);
+    }
+
+    #[test]
+    fn shells_cover_the_sphere_once() {
+        let center = BlockPosition::new(-7, 64, 3);
+        assert_eq!(center.sphere(0).collect::<Vec<_>>(), vec![center]);
+        assert_eq!(center.shell(0).collect::<Vec<_>>(), vec![center]);
+        // The six neighbours, not the center and no diagonals
+        let shell: Vec<BlockPosition> = center.shell(1).collect();
+        assert_eq!(shell.len(), 6);
+        assert!(BlockFace::ALL.iter().all(|face| shell.contains(&center.offset(*face))));
+        // 1 + 6 + 12 + 8 + 6 positions within a distance of 2
+        assert_eq!(center.sphere(2).count(), 33);
+        assert!(center.shell(2).all(|pos| pos.distance_to_ord(&center) > 1 && pos.distance_to_ord(&center) <= 4));
+
+        for radius in 0..6 {
+            let mut shells: Vec<BlockPosition> = (0..=radius).flat_map(|r| center.shell(r)).collect();
+            let mut sphere: Vec<BlockPosition> = center.sphere(radius).collect();
+            shells.sort_by_key(|pos| (pos.x(), pos.y(), pos.z()));
+            sphere.sort_by_key(|pos| (pos.x(), pos.y(), pos.z()));
+            assert_eq!(shells, sphere, "{}", radius);
+
+            let region = Sphere { center, radius };
+            assert!(region.bounds().iter().filter(|pos| region.contains(pos)).eq(center.sphere(radius)));
+        }
+    }
+
+    fn ray(x: Distance, y: Distance, z: Distance, direction: (Distance, Distance, Distance)) -> Ray {
+        Ray::new(Position::new(x, y, z), direction)
+    }
+
+    #[test]
+    fn rays_enter_boxes_through_the_near_face() {
+        let a = unit();
+        // Axis-parallel, from every side
+        assert_eq!(a.ray_intersection(&ray(-2.0, 0.5, 0.5, (1.0, 0.0, 0.0))), Some((2.0, BlockFace::West)));
+        assert_eq!(a.ray_intersection(&ray(3.0, 0.5, 0.5, (-1.0, 0.0, 0.0))), Some((2.0, BlockFace::East)));
+        assert_eq!(a.ray_intersection(&ray(0.5, 5.0, 0.5, (0.0, -2.0, 0.0))), Some((4.0, BlockFace::Up)));
+        assert_eq!(a.ray_intersection(&ray(0.5, -1.0, 0.5, (0.0, 1.0, 0.0))), Some((1.0, BlockFace::Down)));
+        assert_eq!(a.ray_intersection(&ray(0.5, 0.5, 2.0, (0.0, 0.0, -1.0))), Some((1.0, BlockFace::South)));
+        assert_eq!(a.ray_intersection(&ray(0.5, 0.5, -0.5, (0.0, 0.0, 1.0))), Some((0.5, BlockFace::North)));
+
+        // Parallel to the box but beside it, or pointing away
+        assert_eq!(a.ray_intersection(&ray(-2.0, 1.5, 0.5, (1.0, 0.0, 0.0))), None);
+        assert_eq!(a.ray_intersection(&ray(-2.0, 0.5, 0.5, (-1.0, 0.0, 0.0))), None);
+        // Along a face still hits it
+        assert_eq!(a.ray_intersection(&ray(-2.0, 1.0, 0.5, (1.0, 0.0, 0.0))), Some((2.0, BlockFace::West)));
+
+        // From inside, or starting on the face and leaving
+        assert_eq!(a.ray_intersection(&ray(0.5, 0.5, 0.5, (1.0, 0.0, 0.0))), None);
+        assert_eq!(a.ray_intersection(&ray(0.5, 0.5, 0.5, (-1.0, -1.0, -1.0))), None);
+        assert_eq!(a.ray_intersection(&ray(0.0, 0.5, 0.5, (-1.0, 0.0, 0.0))), None);
+
+        // Diagonally with negative directions, the face crossed last is the one entered through
+        let (distance, face) = a.ray_intersection(&ray(2.5, 3.0, 0.5, (-1.0, -1.0, 0.0))).unwrap();
+        assert!((distance - 2.0 * (2.0 as Distance).sqrt()).abs() < 1e-9);
+        assert_eq!(face, BlockFace::Up);
+        let (_, face) = a.ray_intersection(&ray(3.0, 1.5, 0.5, (-1.0, -0.3, 0.0))).unwrap();
+        assert_eq!(face, BlockFace::East);
+    }
+
+    #[test]
+    fn voxels_along_axes() {
+        let blocks = |ray: Ray, max_distance| ray.voxels(max_distance).collect::<Vec<_>>();
+        assert_eq!(blocks(ray(0.5, 64.5, 0.5, (1.0, 0.0, 0.0)), 2.0), vec![BlockPosition::new(0, 64, 0),
+            BlockPosition::new(1, 64, 0), BlockPosition::new(2, 64, 0)]);
+        assert_eq!(blocks(ray(0.5, 64.5, 0.5, (0.0, 0.0, -1.0)), 2.0), vec![BlockPosition::new(0, 64, 0),
+            BlockPosition::new(0, 64, -1), BlockPosition::new(0, 64, -2)]);
+        assert_eq!(blocks(ray(-0.5, 64.5, -0.5, (0.0, -1.0, 0.0)), 1.4), vec![BlockPosition::new(-1, 64, -1),
+            BlockPosition::new(-1, 63, -1)]);
+        // A block is only entered once the ray reaches it
+        assert_eq!(blocks(ray(0.5, 64.5, 0.5, (1.0, 0.0, 0.0)), 0.4), vec![BlockPosition::new(0, 64, 0)]);
+
+        // Starting on a boundary going back
+        assert_eq!(blocks(ray(1.0, 64.5, 0.5, (-1.0, 0.0, 0.0)), 1.5), vec![BlockPosition::new(1, 64, 0),
+            BlockPosition::new(0, 64, 0), BlockPosition::new(-1, 64, 0)]);
+    }
+
+    #[test]
+    fn voxels_are_face_connected() {
+        let directions = [(1.0, 0.3, -0.7), (-0.2, -1.0, 0.05), (-0.6, 0.6, 0.6), (1.0, 1.0, 0.0)];
+        for direction in directions.iter() {
+            let ray = ray(-3.25, 70.5, 8.75, *direction);
+            let blocks: Vec<BlockPosition> = ray.voxels(20.0).collect();
+            assert_eq!(blocks[0], ray.origin().block_position());
+            for pair in blocks.windows(2) {
+                assert_eq!(pair[0].distance_to_ord(&pair[1]), 1, "{:?}", direction);
+            }
+            // Every point along the ray lies in one of them
+            for step in 0..200 {
+                let point = ray.point_at(step as Distance * 0.1);
+                assert!(blocks.contains(&point.block_position()), "{:?} {:?}", direction, point);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+mod v340;
+
+use crate::blocks::{Tool, ToolTier};
+use crate::PROTOCOL_VERSION;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmorSlot {
+    Head,
+    Chest,
+    Legs,
+    Feet
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Food {
+    pub hunger: u8,
+    pub saturation: f32
+}
+
+#[derive(Debug)]
+pub struct ItemInfo {
+    id: u16,
+    name: &'static str,
+    stack_size: u8,
+    max_durability: u16,
+    food: Option<Food>,
+    tool: Option<Tool>,
+    tier: Option<ToolTier>,
+    armor_slot: Option<ArmorSlot>
+}
+
+const fn item(id: u16, name: &'static str, stack_size: u8, max_durability: u16, food: Option<Food>,
+    tool: Option<(Tool, Option<ToolTier>)>, armor_slot: Option<ArmorSlot>) -> ItemInfo {
+    let (tool, tier) = match tool {
+        Some((tool, tier)) => (Some(tool), tier),
+        None => (None, None)
+    };
+    ItemInfo { id, name, stack_size, max_durability, food, tool, tier, armor_slot }
+}
+
+impl ItemInfo {
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn stack_size(&self) -> u8 {
+        self.stack_size
+    }
+
+    pub fn max_durability(&self) -> Option<u16> {
+        if self.max_durability > 0 {
+            Some(self.max_durability)
+        } else {
+            None
+        }
+    }
+
+    pub fn food(&self) -> Option<Food> {
+        self.food
+    }
+
+    pub fn tool(&self) -> Option<Tool> {
+        self.tool
+    }
+
+    pub fn tier(&self) -> Option<ToolTier> {
+        self.tier
+    }
+
+    pub fn armor_slot(&self) -> Option<ArmorSlot> {
+        self.armor_slot
+    }
+
+    pub fn is_block(&self) -> bool {
+        self.id < 256
+    }
+}
+
+pub struct ItemRegistry {
+    protocol_version: i32,
+    items: &'static [ItemInfo]
+}
+
+static REGISTRIES: [ItemRegistry; 1] = [
+    ItemRegistry { protocol_version: 340, items: &v340::ITEMS }
+];
+
+pub fn registry(protocol_version: i32) -> Option<&'static ItemRegistry> {
+    REGISTRIES.iter().find(|r| r.protocol_version == protocol_version)
+}
+
+pub fn item_info(id: u16) -> Option<&'static ItemInfo> {
+    registry(PROTOCOL_VERSION)?.get(id)
+}
+
+pub fn item_by_name(name: &str) -> Option<&'static ItemInfo> {
+    registry(PROTOCOL_VERSION)?.by_name(name)
+}
+
+impl ItemRegistry {
+    pub fn protocol_version(&self) -> i32 {
+        self.protocol_version
+    }
+
+    pub fn get(&self, id: u16) -> Option<&'static ItemInfo> {
+        self.items.binary_search_by_key(&id, |i| i.id)
+            .ok()
+            .map(|idx| &self.items[idx])
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&'static ItemInfo> {
+        let name = name.trim_start_matches("minecraft:");
+        self.items.iter().find(|i| i.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static ItemInfo> {
+        self.items.iter()
+    }
+}
\ No newline at end of file
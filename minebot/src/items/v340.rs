@@ -0,0 +1,423 @@
+use super::{item, Food, ItemInfo};
+use super::ArmorSlot::*;
+use crate::blocks::Tool::*;
+use crate::blocks::ToolTier::*;
+
+// Item table for protocol 340 (Minecraft 1.12.2), sorted by id. Ids below
+// 256 are the item forms of blocks and share their block's name.
+pub static ITEMS: [ItemInfo; 414] = [
+    item(1, "stone", 64, 0, None, None, None),
+    item(2, "grass", 64, 0, None, None, None),
+    item(3, "dirt", 64, 0, None, None, None),
+    item(4, "cobblestone", 64, 0, None, None, None),
+    item(5, "planks", 64, 0, None, None, None),
+    item(6, "sapling", 64, 0, None, None, None),
+    item(7, "bedrock", 64, 0, None, None, None),
+    item(12, "sand", 64, 0, None, None, None),
+    item(13, "gravel", 64, 0, None, None, None),
+    item(14, "gold_ore", 64, 0, None, None, None),
+    item(15, "iron_ore", 64, 0, None, None, None),
+    item(16, "coal_ore", 64, 0, None, None, None),
+    item(17, "log", 64, 0, None, None, None),
+    item(18, "leaves", 64, 0, None, None, None),
+    item(19, "sponge", 64, 0, None, None, None),
+    item(20, "glass", 64, 0, None, None, None),
+    item(21, "lapis_ore", 64, 0, None, None, None),
+    item(22, "lapis_block", 64, 0, None, None, None),
+    item(23, "dispenser", 64, 0, None, None, None),
+    item(24, "sandstone", 64, 0, None, None, None),
+    item(25, "noteblock", 64, 0, None, None, None),
+    item(27, "golden_rail", 64, 0, None, None, None),
+    item(28, "detector_rail", 64, 0, None, None, None),
+    item(29, "sticky_piston", 64, 0, None, None, None),
+    item(30, "web", 64, 0, None, None, None),
+    item(31, "tallgrass", 64, 0, None, None, None),
+    item(32, "deadbush", 64, 0, None, None, None),
+    item(33, "piston", 64, 0, None, None, None),
+    item(35, "wool", 64, 0, None, None, None),
+    item(37, "yellow_flower", 64, 0, None, None, None),
+    item(38, "red_flower", 64, 0, None, None, None),
+    item(39, "brown_mushroom", 64, 0, None, None, None),
+    item(40, "red_mushroom", 64, 0, None, None, None),
+    item(41, "gold_block", 64, 0, None, None, None),
+    item(42, "iron_block", 64, 0, None, None, None),
+    item(43, "double_stone_slab", 64, 0, None, None, None),
+    item(44, "stone_slab", 64, 0, None, None, None),
+    item(45, "brick_block", 64, 0, None, None, None),
+    item(46, "tnt", 64, 0, None, None, None),
+    item(47, "bookshelf", 64, 0, None, None, None),
+    item(48, "mossy_cobblestone", 64, 0, None, None, None),
+    item(49, "obsidian", 64, 0, None, None, None),
+    item(50, "torch", 64, 0, None, None, None),
+    item(52, "mob_spawner", 64, 0, None, None, None),
+    item(53, "oak_stairs", 64, 0, None, None, None),
+    item(54, "chest", 64, 0, None, None, None),
+    item(56, "diamond_ore", 64, 0, None, None, None),
+    item(57, "diamond_block", 64, 0, None, None, None),
+    item(58, "crafting_table", 64, 0, None, None, None),
+    item(60, "farmland", 64, 0, None, None, None),
+    item(61, "furnace", 64, 0, None, None, None),
+    item(65, "ladder", 64, 0, None, None, None),
+    item(66, "rail", 64, 0, None, None, None),
+    item(67, "stone_stairs", 64, 0, None, None, None),
+    item(69, "lever", 64, 0, None, None, None),
+    item(70, "stone_pressure_plate", 64, 0, None, None, None),
+    item(72, "wooden_pressure_plate", 64, 0, None, None, None),
+    item(73, "redstone_ore", 64, 0, None, None, None),
+    item(76, "redstone_torch", 64, 0, None, None, None),
+    item(77, "stone_button", 64, 0, None, None, None),
+    item(78, "snow_layer", 64, 0, None, None, None),
+    item(79, "ice", 64, 0, None, None, None),
+    item(80, "snow", 64, 0, None, None, None),
+    item(81, "cactus", 64, 0, None, None, None),
+    item(82, "clay", 64, 0, None, None, None),
+    item(84, "jukebox", 64, 0, None, None, None),
+    item(85, "fence", 64, 0, None, None, None),
+    item(86, "pumpkin", 64, 0, None, None, None),
+    item(87, "netherrack", 64, 0, None, None, None),
+    item(88, "soul_sand", 64, 0, None, None, None),
+    item(89, "glowstone", 64, 0, None, None, None),
+    item(91, "lit_pumpkin", 64, 0, None, None, None),
+    item(95, "stained_glass", 64, 0, None, None, None),
+    item(96, "trapdoor", 64, 0, None, None, None),
+    item(97, "monster_egg", 64, 0, None, None, None),
+    item(98, "stonebrick", 64, 0, None, None, None),
+    item(99, "brown_mushroom_block", 64, 0, None, None, None),
+    item(100, "red_mushroom_block", 64, 0, None, None, None),
+    item(101, "iron_bars", 64, 0, None, None, None),
+    item(102, "glass_pane", 64, 0, None, None, None),
+    item(103, "melon_block", 64, 0, None, None, None),
+    item(106, "vine", 64, 0, None, None, None),
+    item(107, "fence_gate", 64, 0, None, None, None),
+    item(108, "brick_stairs", 64, 0, None, None, None),
+    item(109, "stone_brick_stairs", 64, 0, None, None, None),
+    item(110, "mycelium", 64, 0, None, None, None),
+    item(111, "waterlily", 64, 0, None, None, None),
+    item(112, "nether_brick", 64, 0, None, None, None),
+    item(113, "nether_brick_fence", 64, 0, None, None, None),
+    item(114, "nether_brick_stairs", 64, 0, None, None, None),
+    item(116, "enchanting_table", 64, 0, None, None, None),
+    item(120, "end_portal_frame", 64, 0, None, None, None),
+    item(121, "end_stone", 64, 0, None, None, None),
+    item(122, "dragon_egg", 64, 0, None, None, None),
+    item(123, "redstone_lamp", 64, 0, None, None, None),
+    item(125, "double_wooden_slab", 64, 0, None, None, None),
+    item(126, "wooden_slab", 64, 0, None, None, None),
+    item(128, "sandstone_stairs", 64, 0, None, None, None),
+    item(129, "emerald_ore", 64, 0, None, None, None),
+    item(130, "ender_chest", 64, 0, None, None, None),
+    item(131, "tripwire_hook", 64, 0, None, None, None),
+    item(133, "emerald_block", 64, 0, None, None, None),
+    item(134, "spruce_stairs", 64, 0, None, None, None),
+    item(135, "birch_stairs", 64, 0, None, None, None),
+    item(136, "jungle_stairs", 64, 0, None, None, None),
+    item(137, "command_block", 64, 0, None, None, None),
+    item(138, "beacon", 64, 0, None, None, None),
+    item(139, "cobblestone_wall", 64, 0, None, None, None),
+    item(143, "wooden_button", 64, 0, None, None, None),
+    item(145, "anvil", 64, 0, None, None, None),
+    item(146, "trapped_chest", 64, 0, None, None, None),
+    item(147, "light_weighted_pressure_plate", 64, 0, None, None, None),
+    item(148, "heavy_weighted_pressure_plate", 64, 0, None, None, None),
+    item(151, "daylight_detector", 64, 0, None, None, None),
+    item(152, "redstone_block", 64, 0, None, None, None),
+    item(153, "quartz_ore", 64, 0, None, None, None),
+    item(154, "hopper", 64, 0, None, None, None),
+    item(155, "quartz_block", 64, 0, None, None, None),
+    item(156, "quartz_stairs", 64, 0, None, None, None),
+    item(157, "activator_rail", 64, 0, None, None, None),
+    item(158, "dropper", 64, 0, None, None, None),
+    item(159, "stained_hardened_clay", 64, 0, None, None, None),
+    item(160, "stained_glass_pane", 64, 0, None, None, None),
+    item(161, "leaves2", 64, 0, None, None, None),
+    item(162, "log2", 64, 0, None, None, None),
+    item(163, "acacia_stairs", 64, 0, None, None, None),
+    item(164, "dark_oak_stairs", 64, 0, None, None, None),
+    item(165, "slime", 64, 0, None, None, None),
+    item(166, "barrier", 64, 0, None, None, None),
+    item(167, "iron_trapdoor", 64, 0, None, None, None),
+    item(168, "prismarine", 64, 0, None, None, None),
+    item(169, "sea_lantern", 64, 0, None, None, None),
+    item(170, "hay_block", 64, 0, None, None, None),
+    item(171, "carpet", 64, 0, None, None, None),
+    item(172, "hardened_clay", 64, 0, None, None, None),
+    item(173, "coal_block", 64, 0, None, None, None),
+    item(174, "packed_ice", 64, 0, None, None, None),
+    item(175, "double_plant", 64, 0, None, None, None),
+    item(179, "red_sandstone", 64, 0, None, None, None),
+    item(180, "red_sandstone_stairs", 64, 0, None, None, None),
+    item(181, "double_stone_slab2", 64, 0, None, None, None),
+    item(182, "stone_slab2", 64, 0, None, None, None),
+    item(183, "spruce_fence_gate", 64, 0, None, None, None),
+    item(184, "birch_fence_gate", 64, 0, None, None, None),
+    item(185, "jungle_fence_gate", 64, 0, None, None, None),
+    item(186, "dark_oak_fence_gate", 64, 0, None, None, None),
+    item(187, "acacia_fence_gate", 64, 0, None, None, None),
+    item(188, "spruce_fence", 64, 0, None, None, None),
+    item(189, "birch_fence", 64, 0, None, None, None),
+    item(190, "jungle_fence", 64, 0, None, None, None),
+    item(191, "dark_oak_fence", 64, 0, None, None, None),
+    item(192, "acacia_fence", 64, 0, None, None, None),
+    item(198, "end_rod", 64, 0, None, None, None),
+    item(199, "chorus_plant", 64, 0, None, None, None),
+    item(200, "chorus_flower", 64, 0, None, None, None),
+    item(201, "purpur_block", 64, 0, None, None, None),
+    item(202, "purpur_pillar", 64, 0, None, None, None),
+    item(203, "purpur_stairs", 64, 0, None, None, None),
+    item(204, "purpur_double_slab", 64, 0, None, None, None),
+    item(205, "purpur_slab", 64, 0, None, None, None),
+    item(206, "end_bricks", 64, 0, None, None, None),
+    item(208, "grass_path", 64, 0, None, None, None),
+    item(210, "repeating_command_block", 64, 0, None, None, None),
+    item(211, "chain_command_block", 64, 0, None, None, None),
+    item(213, "magma", 64, 0, None, None, None),
+    item(214, "nether_wart_block", 64, 0, None, None, None),
+    item(215, "red_nether_brick", 64, 0, None, None, None),
+    item(216, "bone_block", 64, 0, None, None, None),
+    item(217, "structure_void", 64, 0, None, None, None),
+    item(218, "observer", 64, 0, None, None, None),
+    item(219, "white_shulker_box", 1, 0, None, None, None),
+    item(220, "orange_shulker_box", 1, 0, None, None, None),
+    item(221, "magenta_shulker_box", 1, 0, None, None, None),
+    item(222, "light_blue_shulker_box", 1, 0, None, None, None),
+    item(223, "yellow_shulker_box", 1, 0, None, None, None),
+    item(224, "lime_shulker_box", 1, 0, None, None, None),
+    item(225, "pink_shulker_box", 1, 0, None, None, None),
+    item(226, "gray_shulker_box", 1, 0, None, None, None),
+    item(227, "silver_shulker_box", 1, 0, None, None, None),
+    item(228, "cyan_shulker_box", 1, 0, None, None, None),
+    item(229, "purple_shulker_box", 1, 0, None, None, None),
+    item(230, "blue_shulker_box", 1, 0, None, None, None),
+    item(231, "brown_shulker_box", 1, 0, None, None, None),
+    item(232, "green_shulker_box", 1, 0, None, None, None),
+    item(233, "red_shulker_box", 1, 0, None, None, None),
+    item(234, "black_shulker_box", 1, 0, None, None, None),
+    item(235, "white_glazed_terracotta", 64, 0, None, None, None),
+    item(236, "orange_glazed_terracotta", 64, 0, None, None, None),
+    item(237, "magenta_glazed_terracotta", 64, 0, None, None, None),
+    item(238, "light_blue_glazed_terracotta", 64, 0, None, None, None),
+    item(239, "yellow_glazed_terracotta", 64, 0, None, None, None),
+    item(240, "lime_glazed_terracotta", 64, 0, None, None, None),
+    item(241, "pink_glazed_terracotta", 64, 0, None, None, None),
+    item(242, "gray_glazed_terracotta", 64, 0, None, None, None),
+    item(243, "silver_glazed_terracotta", 64, 0, None, None, None),
+    item(244, "cyan_glazed_terracotta", 64, 0, None, None, None),
+    item(245, "purple_glazed_terracotta", 64, 0, None, None, None),
+    item(246, "blue_glazed_terracotta", 64, 0, None, None, None),
+    item(247, "brown_glazed_terracotta", 64, 0, None, None, None),
+    item(248, "green_glazed_terracotta", 64, 0, None, None, None),
+    item(249, "red_glazed_terracotta", 64, 0, None, None, None),
+    item(250, "black_glazed_terracotta", 64, 0, None, None, None),
+    item(251, "concrete", 64, 0, None, None, None),
+    item(252, "concrete_powder", 64, 0, None, None, None),
+    item(255, "structure_block", 64, 0, None, None, None),
+    item(256, "iron_shovel", 1, 250, None, Some((Shovel, Some(Iron))), None),
+    item(257, "iron_pickaxe", 1, 250, None, Some((Pickaxe, Some(Iron))), None),
+    item(258, "iron_axe", 1, 250, None, Some((Axe, Some(Iron))), None),
+    item(259, "flint_and_steel", 1, 64, None, None, None),
+    item(260, "apple", 64, 0, Some(Food { hunger: 4, saturation: 2.4 }), None, None),
+    item(261, "bow", 1, 384, None, None, None),
+    item(262, "arrow", 64, 0, None, None, None),
+    item(263, "coal", 64, 0, None, None, None),
+    item(264, "diamond", 64, 0, None, None, None),
+    item(265, "iron_ingot", 64, 0, None, None, None),
+    item(266, "gold_ingot", 64, 0, None, None, None),
+    item(267, "iron_sword", 1, 250, None, Some((Sword, Some(Iron))), None),
+    item(268, "wooden_sword", 1, 59, None, Some((Sword, Some(Wood))), None),
+    item(269, "wooden_shovel", 1, 59, None, Some((Shovel, Some(Wood))), None),
+    item(270, "wooden_pickaxe", 1, 59, None, Some((Pickaxe, Some(Wood))), None),
+    item(271, "wooden_axe", 1, 59, None, Some((Axe, Some(Wood))), None),
+    item(272, "stone_sword", 1, 131, None, Some((Sword, Some(Stone))), None),
+    item(273, "stone_shovel", 1, 131, None, Some((Shovel, Some(Stone))), None),
+    item(274, "stone_pickaxe", 1, 131, None, Some((Pickaxe, Some(Stone))), None),
+    item(275, "stone_axe", 1, 131, None, Some((Axe, Some(Stone))), None),
+    item(276, "diamond_sword", 1, 1561, None, Some((Sword, Some(Diamond))), None),
+    item(277, "diamond_shovel", 1, 1561, None, Some((Shovel, Some(Diamond))), None),
+    item(278, "diamond_pickaxe", 1, 1561, None, Some((Pickaxe, Some(Diamond))), None),
+    item(279, "diamond_axe", 1, 1561, None, Some((Axe, Some(Diamond))), None),
+    item(280, "stick", 64, 0, None, None, None),
+    item(281, "bowl", 64, 0, None, None, None),
+    item(282, "mushroom_stew", 1, 0, Some(Food { hunger: 6, saturation: 7.2 }), None, None),
+    item(283, "golden_sword", 1, 32, None, Some((Sword, Some(Gold))), None),
+    item(284, "golden_shovel", 1, 32, None, Some((Shovel, Some(Gold))), None),
+    item(285, "golden_pickaxe", 1, 32, None, Some((Pickaxe, Some(Gold))), None),
+    item(286, "golden_axe", 1, 32, None, Some((Axe, Some(Gold))), None),
+    item(287, "string", 64, 0, None, None, None),
+    item(288, "feather", 64, 0, None, None, None),
+    item(289, "gunpowder", 64, 0, None, None, None),
+    item(290, "wooden_hoe", 1, 59, None, Some((Hoe, Some(Wood))), None),
+    item(291, "stone_hoe", 1, 131, None, Some((Hoe, Some(Stone))), None),
+    item(292, "iron_hoe", 1, 250, None, Some((Hoe, Some(Iron))), None),
+    item(293, "diamond_hoe", 1, 1561, None, Some((Hoe, Some(Diamond))), None),
+    item(294, "golden_hoe", 1, 32, None, Some((Hoe, Some(Gold))), None),
+    item(295, "wheat_seeds", 64, 0, None, None, None),
+    item(296, "wheat", 64, 0, None, None, None),
+    item(297, "bread", 64, 0, Some(Food { hunger: 5, saturation: 6.0 }), None, None),
+    item(298, "leather_helmet", 1, 55, None, None, Some(Head)),
+    item(299, "leather_chestplate", 1, 80, None, None, Some(Chest)),
+    item(300, "leather_leggings", 1, 75, None, None, Some(Legs)),
+    item(301, "leather_boots", 1, 65, None, None, Some(Feet)),
+    item(302, "chainmail_helmet", 1, 165, None, None, Some(Head)),
+    item(303, "chainmail_chestplate", 1, 240, None, None, Some(Chest)),
+    item(304, "chainmail_leggings", 1, 225, None, None, Some(Legs)),
+    item(305, "chainmail_boots", 1, 195, None, None, Some(Feet)),
+    item(306, "iron_helmet", 1, 165, None, None, Some(Head)),
+    item(307, "iron_chestplate", 1, 240, None, None, Some(Chest)),
+    item(308, "iron_leggings", 1, 225, None, None, Some(Legs)),
+    item(309, "iron_boots", 1, 195, None, None, Some(Feet)),
+    item(310, "diamond_helmet", 1, 363, None, None, Some(Head)),
+    item(311, "diamond_chestplate", 1, 528, None, None, Some(Chest)),
+    item(312, "diamond_leggings", 1, 495, None, None, Some(Legs)),
+    item(313, "diamond_boots", 1, 429, None, None, Some(Feet)),
+    item(314, "golden_helmet", 1, 77, None, None, Some(Head)),
+    item(315, "golden_chestplate", 1, 112, None, None, Some(Chest)),
+    item(316, "golden_leggings", 1, 105, None, None, Some(Legs)),
+    item(317, "golden_boots", 1, 91, None, None, Some(Feet)),
+    item(318, "flint", 64, 0, None, None, None),
+    item(319, "porkchop", 64, 0, Some(Food { hunger: 3, saturation: 1.8 }), None, None),
+    item(320, "cooked_porkchop", 64, 0, Some(Food { hunger: 8, saturation: 12.8 }), None, None),
+    item(321, "painting", 64, 0, None, None, None),
+    item(322, "golden_apple", 64, 0, Some(Food { hunger: 4, saturation: 9.6 }), None, None),
+    item(323, "sign", 16, 0, None, None, None),
+    item(324, "wooden_door", 64, 0, None, None, None),
+    item(325, "bucket", 16, 0, None, None, None),
+    item(326, "water_bucket", 1, 0, None, None, None),
+    item(327, "lava_bucket", 1, 0, None, None, None),
+    item(328, "minecart", 1, 0, None, None, None),
+    item(329, "saddle", 1, 0, None, None, None),
+    item(330, "iron_door", 64, 0, None, None, None),
+    item(331, "redstone", 64, 0, None, None, None),
+    item(332, "snowball", 16, 0, None, None, None),
+    item(333, "boat", 1, 0, None, None, None),
+    item(334, "leather", 64, 0, None, None, None),
+    item(335, "milk_bucket", 1, 0, None, None, None),
+    item(336, "brick", 64, 0, None, None, None),
+    item(337, "clay_ball", 64, 0, None, None, None),
+    item(338, "reeds", 64, 0, None, None, None),
+    item(339, "paper", 64, 0, None, None, None),
+    item(340, "book", 64, 0, None, None, None),
+    item(341, "slime_ball", 64, 0, None, None, None),
+    item(342, "chest_minecart", 1, 0, None, None, None),
+    item(343, "furnace_minecart", 1, 0, None, None, None),
+    item(344, "egg", 16, 0, None, None, None),
+    item(345, "compass", 64, 0, None, None, None),
+    item(346, "fishing_rod", 1, 64, None, None, None),
+    item(347, "clock", 64, 0, None, None, None),
+    item(348, "glowstone_dust", 64, 0, None, None, None),
+    item(349, "fish", 64, 0, Some(Food { hunger: 2, saturation: 0.4 }), None, None),
+    item(350, "cooked_fish", 64, 0, Some(Food { hunger: 5, saturation: 6.0 }), None, None),
+    item(351, "dye", 64, 0, None, None, None),
+    item(352, "bone", 64, 0, None, None, None),
+    item(353, "sugar", 64, 0, None, None, None),
+    item(354, "cake", 1, 0, None, None, None),
+    item(355, "bed", 1, 0, None, None, None),
+    item(356, "repeater", 64, 0, None, None, None),
+    item(357, "cookie", 64, 0, Some(Food { hunger: 2, saturation: 0.4 }), None, None),
+    item(358, "filled_map", 64, 0, None, None, None),
+    item(359, "shears", 1, 238, None, Some((Shears, None)), None),
+    item(360, "melon", 64, 0, Some(Food { hunger: 2, saturation: 1.2 }), None, None),
+    item(361, "pumpkin_seeds", 64, 0, None, None, None),
+    item(362, "melon_seeds", 64, 0, None, None, None),
+    item(363, "beef", 64, 0, Some(Food { hunger: 3, saturation: 1.8 }), None, None),
+    item(364, "cooked_beef", 64, 0, Some(Food { hunger: 8, saturation: 12.8 }), None, None),
+    item(365, "chicken", 64, 0, Some(Food { hunger: 2, saturation: 1.2 }), None, None),
+    item(366, "cooked_chicken", 64, 0, Some(Food { hunger: 6, saturation: 7.2 }), None, None),
+    item(367, "rotten_flesh", 64, 0, Some(Food { hunger: 4, saturation: 0.8 }), None, None),
+    item(368, "ender_pearl", 16, 0, None, None, None),
+    item(369, "blaze_rod", 64, 0, None, None, None),
+    item(370, "ghast_tear", 64, 0, None, None, None),
+    item(371, "gold_nugget", 64, 0, None, None, None),
+    item(372, "nether_wart", 64, 0, None, None, None),
+    item(373, "potion", 1, 0, None, None, None),
+    item(374, "glass_bottle", 64, 0, None, None, None),
+    item(375, "spider_eye", 64, 0, Some(Food { hunger: 2, saturation: 3.2 }), None, None),
+    item(376, "fermented_spider_eye", 64, 0, None, None, None),
+    item(377, "blaze_powder", 64, 0, None, None, None),
+    item(378, "magma_cream", 64, 0, None, None, None),
+    item(379, "brewing_stand", 64, 0, None, None, None),
+    item(380, "cauldron", 64, 0, None, None, None),
+    item(381, "ender_eye", 64, 0, None, None, None),
+    item(382, "speckled_melon", 64, 0, None, None, None),
+    item(383, "spawn_egg", 64, 0, None, None, None),
+    item(384, "experience_bottle", 64, 0, None, None, None),
+    item(385, "fire_charge", 64, 0, None, None, None),
+    item(386, "writable_book", 1, 0, None, None, None),
+    item(387, "written_book", 16, 0, None, None, None),
+    item(388, "emerald", 64, 0, None, None, None),
+    item(389, "item_frame", 64, 0, None, None, None),
+    item(390, "flower_pot", 64, 0, None, None, None),
+    item(391, "carrot", 64, 0, Some(Food { hunger: 3, saturation: 3.6 }), None, None),
+    item(392, "potato", 64, 0, Some(Food { hunger: 1, saturation: 0.6 }), None, None),
+    item(393, "baked_potato", 64, 0, Some(Food { hunger: 5, saturation: 6.0 }), None, None),
+    item(394, "poisonous_potato", 64, 0, Some(Food { hunger: 2, saturation: 1.2 }), None, None),
+    item(395, "map", 64, 0, None, None, None),
+    item(396, "golden_carrot", 64, 0, Some(Food { hunger: 6, saturation: 14.4 }), None, None),
+    item(397, "skull", 64, 0, None, None, None),
+    item(398, "carrot_on_a_stick", 1, 25, None, None, None),
+    item(399, "nether_star", 64, 0, None, None, None),
+    item(400, "pumpkin_pie", 64, 0, Some(Food { hunger: 8, saturation: 4.8 }), None, None),
+    item(401, "fireworks", 64, 0, None, None, None),
+    item(402, "firework_charge", 64, 0, None, None, None),
+    item(403, "enchanted_book", 1, 0, None, None, None),
+    item(404, "comparator", 64, 0, None, None, None),
+    item(405, "netherbrick", 64, 0, None, None, None),
+    item(406, "quartz", 64, 0, None, None, None),
+    item(407, "tnt_minecart", 1, 0, None, None, None),
+    item(408, "hopper_minecart", 1, 0, None, None, None),
+    item(409, "prismarine_shard", 64, 0, None, None, None),
+    item(410, "prismarine_crystals", 64, 0, None, None, None),
+    item(411, "rabbit", 64, 0, Some(Food { hunger: 3, saturation: 1.8 }), None, None),
+    item(412, "cooked_rabbit", 64, 0, Some(Food { hunger: 5, saturation: 6.0 }), None, None),
+    item(413, "rabbit_stew", 1, 0, Some(Food { hunger: 10, saturation: 12.0 }), None, None),
+    item(414, "rabbit_foot", 64, 0, None, None, None),
+    item(415, "rabbit_hide", 64, 0, None, None, None),
+    item(416, "armor_stand", 16, 0, None, None, None),
+    item(417, "iron_horse_armor", 1, 0, None, None, None),
+    item(418, "golden_horse_armor", 1, 0, None, None, None),
+    item(419, "diamond_horse_armor", 1, 0, None, None, None),
+    item(420, "lead", 64, 0, None, None, None),
+    item(421, "name_tag", 64, 0, None, None, None),
+    item(422, "command_block_minecart", 1, 0, None, None, None),
+    item(423, "mutton", 64, 0, Some(Food { hunger: 2, saturation: 1.2 }), None, None),
+    item(424, "cooked_mutton", 64, 0, Some(Food { hunger: 6, saturation: 9.6 }), None, None),
+    item(425, "banner", 16, 0, None, None, None),
+    item(426, "end_crystal", 64, 0, None, None, None),
+    item(427, "spruce_door", 64, 0, None, None, None),
+    item(428, "birch_door", 64, 0, None, None, None),
+    item(429, "jungle_door", 64, 0, None, None, None),
+    item(430, "acacia_door", 64, 0, None, None, None),
+    item(431, "dark_oak_door", 64, 0, None, None, None),
+    item(432, "chorus_fruit", 64, 0, Some(Food { hunger: 4, saturation: 2.4 }), None, None),
+    item(433, "chorus_fruit_popped", 64, 0, None, None, None),
+    item(434, "beetroot", 64, 0, Some(Food { hunger: 1, saturation: 1.2 }), None, None),
+    item(435, "beetroot_seeds", 64, 0, None, None, None),
+    item(436, "beetroot_soup", 1, 0, Some(Food { hunger: 6, saturation: 7.2 }), None, None),
+    item(437, "dragon_breath", 64, 0, None, None, None),
+    item(438, "splash_potion", 1, 0, None, None, None),
+    item(439, "spectral_arrow", 64, 0, None, None, None),
+    item(440, "tipped_arrow", 64, 0, None, None, None),
+    item(441, "lingering_potion", 1, 0, None, None, None),
+    item(442, "shield", 1, 336, None, None, None),
+    item(443, "elytra", 1, 432, None, None, Some(Chest)),
+    item(444, "spruce_boat", 1, 0, None, None, None),
+    item(445, "birch_boat", 1, 0, None, None, None),
+    item(446, "jungle_boat", 1, 0, None, None, None),
+    item(447, "acacia_boat", 1, 0, None, None, None),
+    item(448, "dark_oak_boat", 1, 0, None, None, None),
+    item(449, "totem_of_undying", 1, 0, None, None, None),
+    item(450, "shulker_shell", 64, 0, None, None, None),
+    item(452, "iron_nugget", 64, 0, None, None, None),
+    item(453, "knowledge_book", 1, 0, None, None, None),
+    item(2256, "record_13", 1, 0, None, None, None),
+    item(2257, "record_cat", 1, 0, None, None, None),
+    item(2258, "record_blocks", 1, 0, None, None, None),
+    item(2259, "record_chirp", 1, 0, None, None, None),
+    item(2260, "record_far", 1, 0, None, None, None),
+    item(2261, "record_mall", 1, 0, None, None, None),
+    item(2262, "record_mellohi", 1, 0, None, None, None),
+    item(2263, "record_stal", 1, 0, None, None, None),
+    item(2264, "record_strad", 1, 0, None, None, None),
+    item(2265, "record_ward", 1, 0, None, None, None),
+    item(2266, "record_11", 1, 0, None, None, None),
+    item(2267, "record_wait", 1, 0, None, None, None),
+];
\ No newline at end of file
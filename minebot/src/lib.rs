@@ -1,41 +1,83 @@
 #[macro_use] extern crate log;
 #[macro_use] extern crate quick_error;
 
+#[cfg(feature = "async")]
+pub mod async_client;
 pub mod blocks;
+pub mod chunk;
 mod clock;
+pub mod connect;
 pub mod events;
+#[cfg(test)]
+mod fake_server;
 mod gamestate;
 pub mod geom;
+pub mod items;
+pub mod path;
+pub mod reconnect;
+pub mod threaded;
+mod watchdog;
 
-use blocks::BlockState;
+use blocks::{BlockQuery, BlockState};
 use clock::Clock;
+use connect::ConnectOptions;
 use events::{Event, EventMatchers};
 use gamestate::GameState;
-use geom::{BlockPosition, Position};
-use nbt::codec::NbtCodec;
+use geom::{BlockFace, BlockPosition, ChunkAddr, Distance, Position, Ray, RayHit, Rotation};
+use json::JsonValue;
+use nbt::DecodeError;
+use nbt::codec::{CodecError, NbtCodec};
 use packets::*;
-use std::net::TcpStream;
+use path::{Action, Goal, Journey, Path, PathOptions, Progress, Step};
+use std::io::ErrorKind;
+use std::net::{Shutdown, TcpStream};
+use std::time::Instant;
+use threaded::ThreadedClient;
 use uuid::Uuid;
+use watchdog::Watchdog;
+
+pub use packets::DimensionId;
+
+pub const PROTOCOL_VERSION: i32 = 340;
+
+// Walking speed is about 4.3 blocks per second
+const MOVE_PER_TICK: f64 = 0.2;
+// Applied to the vertical speed every tick while falling, in blocks per tick
+const GRAVITY: f64 = 0.08;
+const AIR_DRAG: f64 = 0.98;
+const JUMP_VELOCITY: f64 = 0.42;
+// A sprinting jump clears a two block gap before coming back down
+const JUMP_MOVE_PER_TICK: f64 = 0.3;
+const MAX_CHUNK_WAIT_TICKS: u32 = 200;
+const FOLLOW_RETRY_TICKS: u32 = 20;
+const FOLLOW_MAX_REPLANS: u32 = 5;
+const EYE_HEIGHT: f64 = 1.62;
+const REACH: f64 = 4.5;
+
+pub enum FollowTarget {
+    Entity(i32),
+    Player(String)
+}
 
 pub struct MinebotClient {
     sock: TcpStream,
     codec: NbtCodec,
     gamestate: GameState,
-    clock: Clock
+    clock: Clock,
+    watchdog: Watchdog
 }
 
 impl MinebotClient {
     pub fn connect(host: String, port: u16, username: String) -> Result<Self> {
+        Self::connect_with(host, port, username, &ConnectOptions::default())
+    }
+
+    pub fn connect_with(host: String, port: u16, username: String, options: &ConnectOptions) -> Result<Self> {
         info!("Connecting to {}:{}...", host, port);
-        let mut sock = TcpStream::connect((&host as &str, port))?;
+        let mut sock = options.open(&host, port)?;
         let mut codec = NbtCodec::new();
 
-        let packet = HandshakePacket::HandshakePacket {
-            version: 340,
-            host: host,
-            port: port,
-            next: 2
-        };
+        let packet = options.handshake(&host, port);
         trace!("Sending: {:?}", packet);
         codec.send(&mut sock, packet)?;
 
@@ -51,15 +93,21 @@ impl MinebotClient {
             ServerLoginPacket::LoginSuccess { uuid, .. } => {
                 uuid
             }
+            ServerLoginPacket::Disconnect { reason } => {
+                return Err(Error::Disconnected(disconnect_reason(&reason)));
+            }
         };
         info!("Successfully connected, player id is {}", uuid);
 
-        let gamestate = GameState::new(Uuid::parse_str(uuid.as_ref()).unwrap(), username);
+        let uuid = Uuid::parse_str(uuid.as_ref())
+            .map_err(|_| Error::InvalidState(format!("server sent an invalid player id {}", uuid)))?;
+        let gamestate = GameState::new(uuid, username);
         let mut res = MinebotClient {
             sock,
             codec: NbtCodec::new(),
             gamestate,
-            clock: Clock::default()
+            clock: Clock::default(),
+            watchdog: options.watchdog()
         };
 
         res.poll_until(|packet| 
@@ -69,14 +117,7 @@ impl MinebotClient {
             }
         )?;
 
-        res.send(ClientPacket::ClientSettings {
-            locale: "en-US".into(),
-            view_distance: 4,
-            chat_mode: 0,
-            chat_colors: false,
-            displayed_skin: 0xFF,
-            main_hand: 0
-        })?;
+        res.send(options.client_settings())?;
 
         res.poll_until(|packet| 
             match packet {
@@ -90,7 +131,7 @@ impl MinebotClient {
 
     fn send(&mut self, packet: ClientPacket) -> Result<()> {
         trace!("Sending: {:?}", packet);
-        self.codec.send(&mut self.sock, &packet)?;
+        self.codec.send(&mut self.sock, &packet).map_err(CodecError::Io)?;
         Ok(())
     }
 
@@ -130,31 +171,14 @@ impl MinebotClient {
     }
 
     fn handle(&mut self, packet: &ServerPacket) -> Result<()> {
-        self.gamestate.handle_packet(packet);
+        if let Some(ack) = acknowledgement(packet) {
+            self.send(ack)?;
+        }
+        self.gamestate.handle_packet(packet)?;
         self.clock.handle_packet(packet);
-        match *packet {
-            ServerPacket::KeepAlive { id } => {
-                self.send(ClientPacket::KeepAlive {
-                    id: id
-                })?;
-            }
-            ServerPacket::PlayerPositionAndLook { teleport_id, .. } => {
-                if teleport_id != 0 {
-                    self.send(ClientPacket::TeleportConfirm {
-                        teleport_id: teleport_id
-                    })?;
-                }
-                self.send_position()?;
-            }
-            ServerPacket::UpdateHealth { .. } => {
-                if self.gamestate.health() == 0.0 {
-                    self.send(ClientPacket::ClientStatus {
-                        action_id: 0
-                    })?;
-                }
-            }
-            _ => {}
-        };
+        for response in responses(packet, &self.gamestate)? {
+            self.send(response)?;
+        }
         Ok(())
     }
 
@@ -162,6 +186,7 @@ impl MinebotClient {
         self.clock.advance();
         let packet = self.codec.receive_timeout(&mut self.sock, self.clock.current_tick_end())?;
         if let Some(ref got_packet) = packet {
+            self.watchdog.handle_packet(got_packet);
             match &got_packet {
                 ServerPacket::ChunkData { chunk_x, chunk_z, .. } => {
                     trace!("Received: ChunkData {{ chunk_x: {}, chunk_z: {}, ... }}", chunk_x, chunk_z);
@@ -169,9 +194,26 @@ impl MinebotClient {
                 p => trace!("Received: {:?}", p)
             }
         }
+        if self.watchdog.expired() {
+            return Err(Error::Timeout);
+        }
         Ok(packet)
     }
 
+    // Hands the connection to a background thread, see ThreadedClient
+    pub fn into_threaded(self, matchers: EventMatchers) -> Result<ThreadedClient> {
+        ThreadedClient::spawn(self, matchers)
+    }
+
+    // Closes the connection, any further sends or polls fail with Disconnected
+    pub fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting");
+        match self.sock.shutdown(Shutdown::Both) {
+            Err(ref e) if e.kind() == ErrorKind::NotConnected => Ok(()),
+            res => Ok(res?)
+        }
+    }
+
     pub fn health(&self) -> f32 {
         self.gamestate.health()
     }
@@ -180,24 +222,34 @@ impl MinebotClient {
         self.gamestate.food()
     }
 
-    pub fn my_position(&self) -> &Position {
+    pub fn my_position(&self) -> Result<&Position> {
         self.gamestate.my_position()
     }
 
+    pub fn dimension(&self) -> DimensionId {
+        self.gamestate.dimension()
+    }
+
+    pub fn last_death(&self) -> Option<(DimensionId, Position)> {
+        self.gamestate.last_death()
+    }
+
     pub fn say<M: Into<String>>(&mut self, msg: M) -> Result<()> {
         self.send(ClientPacket::ChatMessage { message: msg.into() })
     }
 
     fn send_position(&mut self) -> Result<()> {
-        let position = self.gamestate.my_position();
-        self.send(ClientPacket::PlayerPositionAndLook {
-            x: position.x(),
-            y: position.y(),
-            z: position.z(),
-            yaw: 0f32,
-            pitch: 0f32,
-            on_ground: true
-        })
+        let packet = position_packet(&self.gamestate)?;
+        self.send(packet)
+    }
+
+    pub fn look_at(&mut self, target: &Position) -> Result<()> {
+        let eyes = self.eye_position()?;
+        let (dx, dy, dz) = (target.x() - eyes.x(), target.y() - eyes.y(), target.z() - eyes.z());
+        let yaw = -dx.atan2(dz).to_degrees();
+        let pitch = -dy.atan2((dx * dx + dz * dz).sqrt()).to_degrees();
+        self.gamestate.set_my_rotation(Rotation::new(yaw as f32, pitch as f32))?;
+        self.send_position()
     }
 
     pub fn block_state_at(&self, position: &BlockPosition) -> Option<BlockState> {
@@ -208,15 +260,436 @@ impl MinebotClient {
         self.gamestate.find_block_ids_within(block_id, position, distance)
     }
 
-    pub fn find_path_to(&self, start: BlockPosition, dest: BlockPosition) -> Option<Vec<BlockPosition>> {
+    pub fn find_blocks_within(&self, pred: impl Fn(BlockState) -> bool, position: &BlockPosition, distance: i32) -> Vec<BlockPosition> {
+        self.gamestate.find_blocks_within(pred, position, distance)
+    }
+
+    pub fn find_blocks_matching(&self, query: &BlockQuery, position: &BlockPosition, distance: i32) -> Vec<BlockPosition> {
+        self.gamestate.find_blocks_within(|bs| query.matches(bs), position, distance)
+    }
+
+    pub fn find_path_to(&self, start: BlockPosition, dest: BlockPosition) -> Option<Path> {
         self.gamestate.find_path_to(start, dest)
     }
 
+    pub fn find_path_with(&self, start: BlockPosition, goal: &dyn Goal, options: &PathOptions) -> Option<Path> {
+        self.gamestate.find_path_with(start, goal, options)
+    }
+
+    pub fn travel_to(&mut self, dest: BlockPosition) -> Result<bool> {
+        self.travel_with(dest, PathOptions::default())
+    }
+
+    pub fn travel_with(&mut self, dest: BlockPosition, options: PathOptions) -> Result<bool> {
+        let mut journey = Journey::new(dest, options);
+        let mut waited = 0;
+        loop {
+            let position = self.my_position()?.block_position();
+            match journey.next(&self.gamestate, position) {
+                Progress::Arrived => return Ok(true),
+                Progress::Stuck => return Ok(false),
+                Progress::Wait => {
+                    waited += 1;
+                    if waited > MAX_CHUNK_WAIT_TICKS {
+                        return Ok(false);
+                    }
+                    self.tick(&mut journey)?;
+                }
+                Progress::Move(step) => {
+                    waited = 0;
+                    self.perform_step(&step, &mut journey)?;
+                }
+            }
+        }
+    }
+
+    fn tick(&mut self, journey: &mut Journey) -> Result<()> {
+        let (loaded, changed) = self.wait_tick()?;
+        for addr in loaded {
+            journey.chunk_loaded(addr);
+        }
+        if !changed.is_empty() {
+            journey.blocks_changed(&self.gamestate, self.gamestate.my_position()?.block_position(), &changed);
+        }
+        Ok(())
+    }
+
+    // Keeps handling packets until the current tick is over, however many
+    // arrive in the meantime. Returns the chunks loaded and the blocks changed.
+    fn wait_tick(&mut self) -> Result<(Vec<ChunkAddr>, Vec<BlockPosition>)> {
+        self.clock.advance();
+        let end = self.clock.current_tick_end();
+        let mut loaded = Vec::new();
+        let mut changed = Vec::new();
+        while Instant::now() < end {
+            // The changes are those of the last packet handled, which may
+            // have been in an earlier iteration
+            let packet = match self.poll()? {
+                Some(packet) => packet,
+                None => continue
+            };
+            if let ServerPacket::ChunkData { chunk_x, chunk_z, .. } = packet {
+                loaded.push(ChunkAddr::new(chunk_x, chunk_z));
+            }
+            changed.extend_from_slice(self.gamestate.last_block_changes());
+        }
+        Ok((loaded, changed))
+    }
+
+    fn perform_step(&mut self, step: &Step, journey: &mut Journey) -> Result<()> {
+        let standing_on = self.my_position()?.block_position().with_diff(0, -1, 0);
+        match step.action {
+            Action::Dig(ref blocks) => {
+                for block in blocks {
+                    self.dig(*block, journey)?;
+                    if journey.is_interrupted() {
+                        return Ok(());
+                    }
+                }
+            }
+            Action::Place(block) => {
+                journey.expect_change(block);
+                self.place_block(block, standing_on)?;
+            }
+            Action::Pillar => {
+                let position = *self.my_position()?;
+                self.move_to(position.with_add_y(1.2), journey)?;
+                if journey.is_interrupted() {
+                    return Ok(());
+                }
+                journey.expect_change(standing_on.with_diff(0, 1, 0));
+                self.place_block(standing_on.with_diff(0, 1, 0), standing_on)?;
+            }
+            _ => {}
+        }
+
+        let target = step.position;
+        let target = Position::new(target.x() as f64 + 0.5, target.y() as f64, target.z() as f64 + 0.5);
+        if step.action == Action::Parkour {
+            self.jump_to(target, journey)
+        } else {
+            self.move_to(target, journey)
+        }
+    }
+
+    // Follows the arc of a jump, landing once over the target
+    fn jump_to(&mut self, target: Position, journey: &mut Journey) -> Result<()> {
+        let mut velocity = JUMP_VELOCITY;
+        loop {
+            let position = *self.my_position()?;
+            let (dx, dz) = (target.x() - position.x(), target.z() - position.z());
+            let distance = (dx * dx + dz * dz).sqrt();
+            if distance < 0.01 && position.y() <= target.y() {
+                return Ok(());
+            }
+
+            let scale = if distance <= JUMP_MOVE_PER_TICK { 1.0 } else { JUMP_MOVE_PER_TICK / distance };
+            let mut y = position.y() + velocity;
+            if distance <= JUMP_MOVE_PER_TICK {
+                y = y.max(target.y());
+            }
+            velocity = (velocity - GRAVITY) * AIR_DRAG;
+            self.gamestate.set_my_position(Position::new(position.x() + dx * scale, y, position.z() + dz * scale))?;
+            self.send_position()?;
+            self.tick(journey)?;
+            if journey.is_interrupted() {
+                return Ok(());
+            }
+        }
+    }
+
+    // Climbs before moving over an edge and drops after it, so the bot never
+    // cuts through the corner of a block
+    fn move_to(&mut self, target: Position, journey: &mut Journey) -> Result<()> {
+        let position = *self.my_position()?;
+        if target.y() > position.y() {
+            self.move_straight(position.with_add_y(target.y() - position.y()), journey)?;
+            if journey.is_interrupted() {
+                return Ok(());
+            }
+            self.move_straight(target, journey)
+        } else if target.y() < position.y() && !self.is_floating(&position) {
+            self.move_straight(Position::new(target.x(), position.y(), target.z()), journey)?;
+            if journey.is_interrupted() {
+                return Ok(());
+            }
+            // Once over the edge there's no stopping the fall
+            self.fall_to(target.y(), journey)
+        } else {
+            self.move_straight(target, journey)
+        }
+    }
+
+    // In water or on a ladder, where the bot can go down at its own pace
+    fn is_floating(&self, position: &Position) -> bool {
+        self.block_state_at(&position.block_position())
+            .is_some_and(|bs| bs.is_liquid() || bs.is_climbable())
+    }
+
+    fn fall_to(&mut self, y: f64, journey: &mut Journey) -> Result<()> {
+        let mut velocity = 0.0;
+        loop {
+            let position = *self.my_position()?;
+            if position.y() <= y {
+                return Ok(());
+            }
+
+            velocity = (velocity - GRAVITY) * AIR_DRAG;
+            if self.is_floating(&position) {
+                velocity = velocity.max(-MOVE_PER_TICK);
+            }
+            self.gamestate.set_my_position(position.with_add_y((position.y() + velocity).max(y) - position.y()))?;
+            self.send_position()?;
+            self.tick(journey)?;
+        }
+    }
+
+    fn move_straight(&mut self, target: Position, journey: &mut Journey) -> Result<()> {
+        loop {
+            let position = *self.my_position()?;
+            let distance = position.distance_to(&target);
+            if distance < 0.01 {
+                return Ok(());
+            }
+
+            let next = if distance <= MOVE_PER_TICK {
+                target
+            } else {
+                let scale = MOVE_PER_TICK / distance;
+                position.with_diff((target.x() - position.x()) * scale,
+                    (target.y() - position.y()) * scale,
+                    (target.z() - position.z()) * scale)
+            };
+            self.gamestate.set_my_position(next)?;
+            self.send_position()?;
+            self.tick(journey)?;
+            if journey.is_interrupted() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn dig(&mut self, block: BlockPosition, journey: &mut Journey) -> Result<()> {
+        let state = match self.block_state_at(&block) {
+            Some(state) => state,
+            None => return Ok(())
+        };
+        let ticks = journey.options().dig_ticks(state).unwrap_or(0);
+        let center = Position::new(block.x() as f64 + 0.5, block.y() as f64 + 0.5, block.z() as f64 + 0.5);
+        let face = match self.gamestate.raycast(&Ray::towards(self.eye_position()?, &center), REACH) {
+            Some(hit) if hit.block == block => hit.face,
+            _ => face_towards(block, self.my_position()?.block_position())
+        };
+        self.look_at(&center)?;
+
+        journey.expect_change(block);
+        self.send(ClientPacket::PlayerDigging { status: 0, location: block.to_packed(), face: face.id() as u8 })?;
+        for _ in 0..ticks {
+            self.tick(journey)?;
+            if journey.is_interrupted() {
+                return self.send(ClientPacket::PlayerDigging { status: 1, location: block.to_packed(), face: face.id() as u8 });
+            }
+        }
+        self.send(ClientPacket::PlayerDigging { status: 2, location: block.to_packed(), face: face.id() as u8 })
+    }
+
+    // Clicks a visible face of a neighbouring block, or the face of `fallback`
+    // toward `block` if none can be seen
+    fn place_block(&mut self, block: BlockPosition, fallback: BlockPosition) -> Result<()> {
+        let eyes = self.eye_position()?;
+        let visible = BlockFace::ALL.iter()
+            .filter_map(|face| {
+                let against = block.offset(*face);
+                let (x, y, z) = face.opposite().offset();
+                let face_center = Position::new(against.x() as f64 + 0.5 + x as f64 * 0.5,
+                    against.y() as f64 + 0.5 + y as f64 * 0.5,
+                    against.z() as f64 + 0.5 + z as f64 * 0.5);
+                let hit = self.gamestate.raycast(&Ray::towards(eyes, &face_center), REACH)?;
+                if hit.block == against && hit.face == face.opposite() { Some(hit) } else { None }
+            })
+            .min_by(|hit1, hit2| hit1.distance.partial_cmp(&hit2.distance).unwrap());
+
+        let (against, face, cursor) = match visible {
+            Some(hit) => (hit.block, hit.face, hit.point.with_diff(-hit.block.x() as f64, -hit.block.y() as f64, -hit.block.z() as f64)),
+            None => (fallback, face_towards(fallback, block), Position::new(0.5, 0.5, 0.5))
+        };
+        self.look_at(&cursor.with_diff(against.x() as f64, against.y() as f64, against.z() as f64))?;
+        self.send(ClientPacket::PlayerBlockPlacement {
+            location: against.to_packed(),
+            face: face.id(),
+            hand: 0,
+            cursor_x: cursor.x() as f32,
+            cursor_y: cursor.y() as f32,
+            cursor_z: cursor.z() as f32
+        })
+    }
+
+    fn eye_position(&self) -> Result<Position> {
+        Ok(self.my_position()?.with_add_y(EYE_HEIGHT))
+    }
+
+    // The block under the crosshair, if within reach
+    pub fn target_block(&self) -> Result<Option<RayHit>> {
+        let ray = Ray::from_rotation(self.eye_position()?, self.gamestate.my_rotation()?);
+        Ok(self.gamestate.raycast(&ray, REACH))
+    }
+
+    pub fn can_see(&self, position: &Position) -> Result<bool> {
+        Ok(self.gamestate.has_line_of_sight(&self.eye_position()?, position))
+    }
+
+    pub fn can_see_entity(&self, entity_id: i32) -> Result<bool> {
+        let position = self.gamestate.entity_position(entity_id)
+            .ok_or(Error::UnknownEntity(entity_id))?;
+        self.can_see(&position.with_add_y(EYE_HEIGHT))
+    }
+
+    // Keeps within max_dist of the target, standing still and watching it once
+    // within min_dist. Fails with UnknownEntity once the target leaves render
+    // distance or disconnects, and with NoPath if it can't be reached after
+    // several attempts.
+    pub fn follow(&mut self, target: FollowTarget, min_dist: Distance, max_dist: Distance) -> Result<()> {
+        let entity_id = match target {
+            FollowTarget::Entity(entity_id) => entity_id,
+            FollowTarget::Player(ref name) => self.gamestate.player_entity_id(name)
+                .ok_or_else(|| Error::InvalidState(format!("player {} is not in sight", name)))?
+        };
+        let mut journey: Option<Journey> = None;
+        let mut looking_at = None;
+        let mut failed_replans = 0;
+        let mut waited = 0;
+        loop {
+            let target_position = self.gamestate.entity_position(entity_id)
+                .ok_or(Error::UnknownEntity(entity_id))?;
+            let position = *self.my_position()?;
+            let distance = position.distance_to(&target_position);
+            let target_block = target_position.block_position();
+
+            if distance <= min_dist {
+                journey = None;
+            } else if distance > max_dist {
+                // Only re-path once the target has moved away from the old destination
+                let stale = journey.as_ref()
+                    .is_none_or(|journey| journey.dest().distance_to(&target_block) > (max_dist - min_dist).max(1.0));
+                if stale {
+                    journey = Some(Journey::new(target_block, PathOptions::default()));
+                }
+            }
+
+            if let Some(ref mut current) = journey {
+                let gave_up = match current.next(&self.gamestate, position.block_position()) {
+                    Progress::Move(step) => {
+                        failed_replans = 0;
+                        waited = 0;
+                        looking_at = None;
+                        self.perform_step(&step, current)?;
+                        continue;
+                    }
+                    Progress::Wait => {
+                        waited += 1;
+                        if waited <= MAX_CHUNK_WAIT_TICKS {
+                            self.tick(current)?;
+                            continue;
+                        }
+                        true
+                    }
+                    Progress::Stuck => true,
+                    Progress::Arrived => false
+                };
+                journey = None;
+                if gave_up {
+                    failed_replans += 1;
+                    if failed_replans >= FOLLOW_MAX_REPLANS {
+                        return Err(Error::NoPath);
+                    }
+                    waited = 0;
+                    for _ in 0..FOLLOW_RETRY_TICKS {
+                        self.wait_tick()?;
+                    }
+                    continue;
+                }
+            }
+
+            // Only turn when the target has moved
+            let eyes = target_position.with_add_y(EYE_HEIGHT);
+            if looking_at != Some(eyes) {
+                self.look_at(&eyes)?;
+                looking_at = Some(eyes);
+            }
+            self.wait_tick()?;
+        }
+    }
+
     pub fn player_names(&self) -> Vec<&str> {
         self.gamestate.player_names()
     }
 }
 
+// What the server expects back at once, without looking at the game state
+fn acknowledgement(packet: &ServerPacket) -> Option<ClientPacket> {
+    match *packet {
+        ServerPacket::KeepAlive { id } => Some(ClientPacket::KeepAlive {
+            id
+        }),
+        ServerPacket::PlayerPositionAndLook { teleport_id, .. } if teleport_id != 0 => Some(ClientPacket::TeleportConfirm {
+            teleport_id
+        }),
+        _ => None
+    }
+}
+
+// What the client has to answer to a packet once the game state has been
+// updated with it, after the acknowledgement
+fn responses(packet: &ServerPacket, gamestate: &GameState) -> Result<Vec<ClientPacket>> {
+    match *packet {
+        ServerPacket::Disconnect { ref reason } => {
+            Err(Error::Disconnected(disconnect_reason(reason)))
+        }
+        ServerPacket::PlayerPositionAndLook { .. } => {
+            Ok(vec![position_packet(gamestate)?])
+        }
+        ServerPacket::UpdateHealth { .. } if gamestate.health() == 0.0 => {
+            Ok(vec![ClientPacket::ClientStatus {
+                action_id: 0
+            }])
+        }
+        _ => Ok(Vec::new())
+    }
+}
+
+fn position_packet(gamestate: &GameState) -> Result<ClientPacket> {
+    let position = gamestate.my_position()?;
+    let rotation = gamestate.my_rotation()?;
+    Ok(ClientPacket::PlayerPositionAndLook {
+        x: position.x(),
+        y: position.y(),
+        z: position.z(),
+        yaw: rotation.yaw(),
+        pitch: rotation.pitch(),
+        on_ground: gamestate.is_on_ground()?
+    })
+}
+
+fn disconnect_reason(reason: &JsonValue) -> String {
+    reason["text"].as_str()
+        .map(|text| text.to_owned())
+        .unwrap_or_else(|| reason.dump())
+}
+
+// Face of `block` that points toward `other`
+fn face_towards(block: BlockPosition, other: BlockPosition) -> BlockFace {
+    let (dx, dy, dz) = (other.x() - block.x(), other.y() - block.y(), other.z() - block.z());
+    if dy.abs() >= dx.abs() && dy.abs() >= dz.abs() {
+        if dy < 0 { BlockFace::Down } else { BlockFace::Up }
+    } else if dz.abs() >= dx.abs() {
+        if dz < 0 { BlockFace::North } else { BlockFace::South }
+    } else if dx < 0 {
+        BlockFace::West
+    } else {
+        BlockFace::East
+    }
+}
+
 quick_error! {
     #[derive(Debug)]
     pub enum Error {
@@ -225,7 +698,159 @@ quick_error! {
             cause(err)
             from()
         }
+        Decode(err: DecodeError) {
+            description(err.description())
+            display("could not decode packet: {}", err)
+            cause(err)
+            from()
+        }
+        Disconnected(reason: String) {
+            description("disconnected from server")
+            display("disconnected from server: {}", reason)
+        }
+        Timeout {
+            description("timed out waiting for the server")
+        }
+        NotSpawned {
+            description("the player has not spawned yet")
+        }
+        NoPath {
+            description("no path to the destination")
+        }
+        UnknownEntity(entity_id: i32) {
+            description("unknown entity")
+            display("unknown entity {}", entity_id)
+        }
+        InvalidState(message: String) {
+            description("invalid state")
+            display("invalid state: {}", message)
+        }
     }
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+impl Error {
+    // The connection can't be used any more, as opposed to a bad packet or a
+    // request that doesn't fit the current state
+    pub fn is_connection_error(&self) -> bool {
+        matches!(*self, Error::Io(_) | Error::Disconnected(_) | Error::Timeout)
+    }
+}
+
+impl From<CodecError> for Error {
+    fn from(err: CodecError) -> Self {
+        match err {
+            CodecError::Io(err) => match err.kind() {
+                ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe =>
+                    Error::Disconnected(err.to_string()),
+                ErrorKind::WouldBlock | ErrorKind::TimedOut => Error::Timeout,
+                _ => Error::Io(err)
+            },
+            CodecError::Decode(err) => Error::Decode(err)
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_server::{Connection, FakeServer};
+    use std::thread;
+    use std::time::Duration;
+
+    fn connect() -> (MinebotClient, Connection) {
+        let server = FakeServer::bind();
+        let port = server.port();
+        let server = thread::spawn(move || server.accept());
+        let options = ConnectOptions::new().silence_timeout(Duration::from_secs(30));
+        let client = MinebotClient::connect_with("127.0.0.1".to_owned(), port, "bot".to_owned(), &options).unwrap();
+        (client, server.join().unwrap())
+    }
+
+    // Standing on a stone floor at (0, 65, 0), with a zombie as entity 2
+    fn on_floor_with_zombie(client: &mut MinebotClient, x: f64, z: f64) {
+        client.gamestate = GameState::with_chunks(Position::new(0.5, 65.0, 0.5), -1, -1, 1, 1);
+        client.gamestate.fill(BlockPosition::new(-16, 64, -16), BlockPosition::new(31, 64, 31), BlockState(1 << 4));
+        client.gamestate.handle_packet(&ServerPacket::SpawnMob {
+            entity_id: 2,
+            uuid: Uuid::nil(),
+            mob_type: 54,
+            x,
+            y: 65.0,
+            z,
+            yaw: 0,
+            pitch: 0,
+            head_pitch: 0,
+            velocity_x: 0,
+            velocity_y: 0,
+            velocity_z: 0
+        }).unwrap();
+    }
+
+    #[test]
+    fn own_changes_are_not_repeated_by_quiet_ticks() {
+        let (mut client, mut conn) = connect();
+        client.gamestate = GameState::with_chunks(Position::new(0.5, 65.0, 0.5), 0, 0, 0, 0);
+        client.gamestate.fill(BlockPosition::new(0, 64, 0), BlockPosition::new(15, 64, 15), BlockState(1 << 4));
+
+        let mut journey = Journey::new(BlockPosition::new(8, 65, 0), PathOptions::default());
+        let step = match journey.next(&client.gamestate, BlockPosition::new(0, 65, 0)) {
+            Progress::Move(step) => step,
+            _ => panic!("no first step")
+        };
+        let dug = step.position.with_diff(0, -1, 0);
+        journey.expect_change(dug);
+        conn.block_change(dug.to_packed(), 0);
+        while client.block_state_at(&dug) != Some(BlockState(0)) {
+            client.tick(&mut journey).unwrap();
+            assert!(!journey.is_interrupted());
+        }
+
+        let (loaded, changed) = client.wait_tick().unwrap();
+        assert!(loaded.is_empty());
+        assert!(changed.is_empty());
+        client.tick(&mut journey).unwrap();
+        assert!(!journey.is_interrupted());
+
+        // Someone else's change is reported once
+        let placed = step.position.with_diff(0, 1, 0);
+        conn.block_change(placed.to_packed(), 1 << 4);
+        let mut changed = Vec::new();
+        while client.block_state_at(&placed) != Some(BlockState(1 << 4)) {
+            changed.extend(client.wait_tick().unwrap().1);
+        }
+        changed.extend(client.wait_tick().unwrap().1);
+        assert_eq!(changed, vec![placed]);
+    }
+
+    #[test]
+    fn follow_ends_when_the_target_is_gone() {
+        let (mut client, mut conn) = connect();
+        on_floor_with_zombie(&mut client, 2.5, 0.5);
+        conn.destroy_entities(&[2]);
+        match client.follow(FollowTarget::Entity(2), 3.0, 5.0) {
+            Err(Error::UnknownEntity(2)) => (),
+            res => panic!("expected UnknownEntity, got {:?}", res)
+        }
+
+        match client.follow(FollowTarget::Player("alex".to_owned()), 3.0, 5.0) {
+            Err(Error::InvalidState(_)) => (),
+            res => panic!("expected InvalidState, got {:?}", res)
+        }
+    }
+
+    #[test]
+    fn follow_gives_up_on_unreachable_targets() {
+        let (mut client, _conn) = connect();
+        on_floor_with_zombie(&mut client, 10.5, 10.5);
+        // Walled in on all sides
+        let stone = BlockState(1 << 4);
+        client.gamestate.fill(BlockPosition::new(-1, 65, -1), BlockPosition::new(1, 66, 1), stone);
+        client.gamestate.fill(BlockPosition::new(0, 65, 0), BlockPosition::new(0, 66, 0), BlockState(0));
+        client.gamestate.set_block_state(&BlockPosition::new(0, 67, 0), stone);
+        match client.follow(FollowTarget::Entity(2), 1.0, 2.0) {
+            Err(Error::NoPath) => (),
+            res => panic!("expected NoPath, got {:?}", res)
+        }
+    }
+}
@@ -20,6 +20,6 @@ fn main() {
 }
 
 fn ping_position(client: &mut MinebotClient) -> Result<()> {
-    let position = client.my_position();
+    let position = *client.my_position()?;
     client.say(format!("My position is: ({}, {}, {})", position.x(), position.y(), position.z()))
 }
\ No newline at end of file
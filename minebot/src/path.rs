@@ -0,0 +1,1135 @@
+mod goal;
+
+use crate::blocks::{BlockState, Tool, ToolTier};
+use crate::gamestate::GameState;
+use crate::geom::{BlockPosition, ChunkAddr, Distance, Position};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+pub use goal::{AnyOf, AwayFrom, Composite, Exact, Goal, Near, XZ, YLevel};
+
+const WALK_COST: u64 = 10;
+const STEP_UP_COST: u64 = 20;
+const DROP_COST: u64 = 15;
+const CLIMB_COST: u64 = 15;
+const SWIM_COST: u64 = 20;
+const DIG_TICK_COST: u64 = 2;
+const PLACE_COST: u64 = 40;
+const PILLAR_COST: u64 = 50;
+const DIAGONAL_COST: u64 = 14;
+const FALL_BLOCK_COST: u64 = 2;
+const PARKOUR_COST: u64 = 15;
+const UNLOADED_COST: u64 = 15;
+const JOURNEY_MAX_NODES: usize = 100_000;
+// How far past a change a repaired path rejoins the old one
+const REPAIR_WINDOW: usize = 8;
+const REPAIR_MAX_NODES: usize = 5_000;
+
+// Falls of up to three blocks deal no damage, every block beyond costs half a heart
+const SAFE_FALL: u32 = 3;
+const MAX_WATER_FALL: u32 = 64;
+
+const CARDINALS: [(i32, i32); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
+const DIAGONALS: [(i32, i32); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Walk,
+    Jump,
+    Fall,
+    Parkour,
+    Climb,
+    Swim,
+    Dig(Vec<BlockPosition>),
+    Place(BlockPosition),
+    Pillar,
+    // Enters terrain that is not loaded yet, the path ends here
+    Explore
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub position: BlockPosition,
+    pub action: Action
+}
+
+#[derive(Debug, Clone)]
+pub struct Path {
+    pub steps: Vec<Step>,
+    // false when the search gave up and this only leads closer to the goal
+    pub complete: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct CostModel {
+    // Extra cost for standing next to lava, fire, cactus or magma, None to
+    // never path there
+    pub hazard_cost: Option<u64>,
+    // Extra cost per half heart of fall damage
+    pub fall_damage_cost: u64,
+    pub mob_radius: Distance,
+    // Extra cost within mob_radius of a hostile mob, None to never path there
+    pub mob_cost: Option<u64>
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        CostModel {
+            hazard_cost: Some(50),
+            fall_damage_cost: 20,
+            mob_radius: 6.0,
+            mob_cost: Some(100)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PathOptions {
+    pub may_dig: bool,
+    pub may_place: bool,
+    pub place_budget: u32,
+    pub tools: Vec<(Tool, Option<ToolTier>)>,
+    pub may_parkour: bool,
+    pub max_fall: u32,
+    pub max_nodes: Option<usize>,
+    pub timeout: Option<Duration>,
+    pub partial: bool,
+    pub through_unloaded: bool,
+    pub costs: CostModel
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        PathOptions {
+            may_dig: false,
+            may_place: false,
+            place_budget: 0,
+            tools: Vec::default(),
+            // A jump that falls short can end far below, only taken when asked for
+            may_parkour: false,
+            max_fall: SAFE_FALL,
+            max_nodes: None,
+            timeout: None,
+            partial: false,
+            through_unloaded: false,
+            costs: CostModel::default()
+        }
+    }
+}
+
+impl PathOptions {
+    pub fn dig_ticks(&self, state: BlockState) -> Option<u32> {
+        let by_hand = state.dig_ticks(None);
+        self.tools.iter()
+            .filter_map(|tool| state.dig_ticks(Some(*tool)))
+            .chain(by_hand)
+            .min()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Node {
+    pos: BlockPosition,
+    placed: u32
+}
+
+#[derive(PartialEq, Eq)]
+struct Candidate {
+    estimate: u64,
+    cost: u64,
+    node: Node
+}
+
+impl Ord for Candidate {
+    // Reversed so that the binary heap pops the cheapest estimate first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub fn find_path(gamestate: &GameState, start: BlockPosition, goal: &dyn Goal, options: &PathOptions) -> Option<Path> {
+    // Keep at least one heart after landing
+    let survivable = SAFE_FALL + ((gamestate.health() - 1.0).max(0.0) * 2.0) as u32;
+    let planner = Planner {
+        gamestate,
+        options,
+        max_fall: options.max_fall.min(survivable),
+        hostiles: gamestate.hostile_positions()
+    };
+    let heuristic = |node: &Node| (goal.heuristic(&node.pos) * WALK_COST as f64) as u64;
+    let started = Instant::now();
+
+    let start = Node { pos: start, placed: 0 };
+    let mut parents: HashMap<Node, (Option<Node>, u64)> = HashMap::default();
+    parents.insert(start, (None, 0));
+    let mut open = BinaryHeap::new();
+    open.push(Candidate { estimate: heuristic(&start), cost: 0, node: start });
+    let mut closest = (heuristic(&start), start);
+    let mut expanded = 0;
+
+    while let Some(Candidate { cost, node, .. }) = open.pop() {
+        if parents[&node].1 < cost {
+            continue;
+        }
+        if goal.is_reached(&node.pos) {
+            return Some(planner.path_to(&parents, node, true));
+        }
+        // The rest of the route lies in unloaded terrain and was already
+        // estimated as a straight line, searching it would only be a guess
+        if planner.is_unexplored(node.pos) {
+            return Some(planner.path_to(&parents, node, false));
+        }
+
+        expanded += 1;
+        if options.max_nodes.is_some_and(|max| expanded > max) ||
+                options.timeout.is_some_and(|timeout| started.elapsed() > timeout) {
+            break;
+        }
+
+        for (next, _, step_cost) in planner.moves(&node) {
+            let next_cost = cost + step_cost;
+            if parents.get(&next).is_none_or(|&(_, known)| next_cost < known) {
+                parents.insert(next, (Some(node), next_cost));
+                let mut estimate = heuristic(&next);
+                if estimate < closest.0 {
+                    closest = (estimate, next);
+                }
+                if planner.is_unexplored(next.pos) {
+                    estimate = estimate * UNLOADED_COST / WALK_COST;
+                }
+                open.push(Candidate { estimate: next_cost + estimate, cost: next_cost, node: next });
+            }
+        }
+    }
+
+    if options.partial && closest.1 != start {
+        Some(planner.path_to(&parents, closest.1, false))
+    } else {
+        None
+    }
+}
+
+struct Planner<'a> {
+    gamestate: &'a GameState,
+    options: &'a PathOptions,
+    max_fall: u32,
+    hostiles: Vec<Position>
+}
+
+impl <'a> Planner<'a> {
+    fn path_to(&self, parents: &HashMap<Node, (Option<Node>, u64)>, end: Node, complete: bool) -> Path {
+        let mut nodes = vec![end];
+        while let Some((Some(parent), _)) = parents.get(nodes.last().unwrap()) {
+            nodes.push(*parent);
+        }
+        nodes.reverse();
+
+        Path {
+            steps: nodes.windows(2)
+                .map(|pair| self.step_between(&pair[0], &pair[1]))
+                .collect(),
+            complete
+        }
+    }
+
+    fn step_between(&self, from: &Node, to: &Node) -> Step {
+        let action = self.moves(from).into_iter()
+            .filter(|(next, _, _)| next == to)
+            .min_by_key(|(_, _, cost)| *cost)
+            .map(|(_, action, _)| action)
+            .unwrap_or(Action::Walk);
+        Step {
+            position: to.pos,
+            action
+        }
+    }
+
+    fn moves(&self, node: &Node) -> Vec<(Node, Action, u64)> {
+        let mut result = Vec::default();
+        let pos = node.pos;
+        let at = |pos: BlockPosition| Node { pos, placed: node.placed };
+
+        for (x, z) in CARDINALS.iter() {
+            let feet = pos.with_diff(*x, 0, *z);
+            let head = feet.with_diff(0, 1, 0);
+            let below = feet.with_diff(0, -1, 0);
+            if self.is_unexplored(feet) {
+                result.push((at(feet), Action::Explore, UNLOADED_COST));
+                continue;
+            }
+
+            if self.is_passable(head) {
+                if self.is_passable(feet) {
+                    if self.is_water(feet) {
+                        result.push((at(feet), Action::Swim, SWIM_COST));
+                    } else if self.can_stay_at(feet) {
+                        result.push((at(feet), Action::Walk, WALK_COST));
+                    } else {
+                        if let Some((landing, height)) = self.fall_landing(feet) {
+                            let damage = if self.is_water(landing) || self.is_climbable(landing) {
+                                0
+                            } else {
+                                height.saturating_sub(SAFE_FALL) as u64
+                            };
+                            let cost = DROP_COST + height as u64 * FALL_BLOCK_COST + damage * self.options.costs.fall_damage_cost;
+                            result.push((at(landing), Action::Fall, cost));
+                        }
+                        if let Some((landing, gap)) = self.parkour_landing(pos, *x, *z) {
+                            result.push((at(landing), Action::Parkour, PARKOUR_COST * (gap as u64 + 1)));
+                        }
+                        if self.can_place(node) && self.is_passable(below) {
+                            let placed = Node { pos: feet, placed: node.placed + 1 };
+                            result.push((placed, Action::Place(below), WALK_COST + PLACE_COST));
+                        }
+                    }
+                    continue;
+                } else if self.can_stand_on(feet) && self.is_passable(head.with_diff(0, 1, 0)) &&
+                        self.is_passable(pos.with_diff(0, 2, 0)) {
+                    result.push((at(feet.with_diff(0, 1, 0)), Action::Jump, STEP_UP_COST));
+                    continue;
+                }
+            }
+
+            if self.options.may_dig && self.can_stand_on(below) {
+                let blocks: Vec<BlockPosition> = [feet, head].iter()
+                    .cloned()
+                    .filter(|p| !self.is_passable(*p))
+                    .collect();
+                let dig_cost = blocks.iter()
+                    .map(|p| self.dig_cost(*p))
+                    .try_fold(0, |total, cost| Some(total + cost?));
+                if let Some(dig_cost) = dig_cost {
+                    result.push((at(feet), Action::Dig(blocks), WALK_COST + dig_cost));
+                }
+            }
+        }
+
+        for (x, z) in DIAGONALS.iter() {
+            let feet = pos.with_diff(*x, 0, *z);
+            if self.is_unexplored(feet) {
+                result.push((at(feet), Action::Explore, UNLOADED_COST * DIAGONAL_COST / WALK_COST));
+                continue;
+            }
+
+            // Both corners have to be clear or the bot gets caught on them
+            let clear = [feet, pos.with_diff(*x, 0, 0), pos.with_diff(0, 0, *z)].iter()
+                .all(|p| self.is_passable(*p) && self.is_passable(p.with_diff(0, 1, 0)));
+            if !clear {
+                continue;
+            }
+
+            if self.is_water(feet) {
+                result.push((at(feet), Action::Swim, SWIM_COST * DIAGONAL_COST / WALK_COST));
+            } else if self.can_stand_on(feet.with_diff(0, -1, 0)) {
+                result.push((at(feet), Action::Walk, DIAGONAL_COST));
+            }
+        }
+
+        let up = pos.with_diff(0, 1, 0);
+        let down = pos.with_diff(0, -1, 0);
+        if self.is_passable(up) && self.is_passable(pos.with_diff(0, 2, 0)) {
+            if self.is_climbable(pos) {
+                result.push((at(up), Action::Climb, CLIMB_COST));
+            } else if self.is_water(pos) {
+                result.push((at(up), Action::Swim, SWIM_COST));
+            } else if self.can_place(node) {
+                result.push((Node { pos: up, placed: node.placed + 1 }, Action::Pillar, PILLAR_COST));
+            }
+        }
+
+        if self.is_climbable(down) {
+            result.push((at(down), Action::Climb, CLIMB_COST));
+        } else if self.is_water(down) {
+            result.push((at(down), Action::Swim, SWIM_COST));
+        } else if self.options.may_dig && self.can_stay_at(down) {
+            if let Some(dig_cost) = self.dig_cost(down) {
+                result.push((at(down), Action::Dig(vec![down]), DROP_COST + dig_cost));
+            }
+        }
+
+        result.into_iter()
+            .filter_map(|(next, action, cost)| Some((next, action, cost + self.danger_cost(next.pos)?)))
+            .collect()
+    }
+
+    // None if the position must be avoided entirely
+    fn danger_cost(&self, pos: BlockPosition) -> Option<u64> {
+        let costs = &self.options.costs;
+        let mut cost = 0;
+
+        let near_hazard = [(0, -1, 0), (0, 2, 0), (-1, 0, 0), (1, 0, 0), (0, 0, -1), (0, 0, 1),
+                (-1, 1, 0), (1, 1, 0), (0, 1, -1), (0, 1, 1)].iter()
+            .any(|(x, y, z)| self.block_at(pos.with_diff(*x, *y, *z)).is_some_and(|bs| bs.is_dangerous()));
+        if near_hazard {
+            cost += costs.hazard_cost?;
+        }
+
+        let center = Position::new(pos.x() as f64 + 0.5, pos.y() as f64, pos.z() as f64 + 0.5);
+        if self.hostiles.iter().any(|mob| mob.distance_to(&center) <= costs.mob_radius) {
+            cost += costs.mob_cost?;
+        }
+
+        Some(cost)
+    }
+
+    fn fall_landing(&self, feet: BlockPosition) -> Option<(BlockPosition, u32)> {
+        for height in 1..=MAX_WATER_FALL {
+            let pos = feet.with_diff(0, -(height as i32), 0);
+            if !self.is_passable(pos) {
+                return None;
+            }
+            if self.is_water(pos) || self.is_climbable(pos) {
+                return Some((pos, height));
+            }
+            if self.can_stand_on(pos.with_diff(0, -1, 0)) {
+                return if height <= self.max_fall { Some((pos, height)) } else { None };
+            }
+        }
+        None
+    }
+
+    fn parkour_landing(&self, pos: BlockPosition, x: i32, z: i32) -> Option<(BlockPosition, u32)> {
+        if !self.options.may_parkour || !self.can_stand_on(pos.with_diff(0, -1, 0)) ||
+                !self.is_passable(pos.with_diff(0, 2, 0)) {
+            return None;
+        }
+
+        for gap in 1..=2 {
+            let over = pos.with_diff(x * gap, 0, z * gap);
+            if ![over, over.with_diff(0, 1, 0), over.with_diff(0, 2, 0)].iter().all(|p| self.is_passable(*p)) {
+                return None;
+            }
+
+            let landing = pos.with_diff(x * (gap + 1), 0, z * (gap + 1));
+            if self.is_passable(landing) && self.is_passable(landing.with_diff(0, 1, 0)) &&
+                    self.is_passable(landing.with_diff(0, 2, 0)) && self.can_stand_on(landing.with_diff(0, -1, 0)) {
+                return Some((landing, gap as u32));
+            }
+        }
+        None
+    }
+
+    // Unloaded terrain is assumed to be flat and walkable until its chunk arrives
+    fn is_unexplored(&self, pos: BlockPosition) -> bool {
+        self.options.through_unloaded && !self.gamestate.is_chunk_loaded(pos.chunk())
+    }
+
+    fn block_at(&self, pos: BlockPosition) -> Option<BlockState> {
+        self.gamestate.block_state_at(&pos)
+    }
+
+    fn is_passable(&self, pos: BlockPosition) -> bool {
+        self.block_at(pos).is_some_and(|bs| bs.is_passable())
+    }
+
+    fn can_stand_on(&self, pos: BlockPosition) -> bool {
+        self.block_at(pos).is_some_and(|bs| bs.can_stand_on())
+    }
+
+    fn is_water(&self, pos: BlockPosition) -> bool {
+        self.block_at(pos).is_some_and(|bs| bs.is_water())
+    }
+
+    fn is_climbable(&self, pos: BlockPosition) -> bool {
+        self.block_at(pos).is_some_and(|bs| bs.is_climbable())
+    }
+
+    fn can_stay_at(&self, pos: BlockPosition) -> bool {
+        self.can_stand_on(pos.with_diff(0, -1, 0)) || self.is_water(pos) || self.is_climbable(pos)
+    }
+
+    fn can_place(&self, node: &Node) -> bool {
+        self.options.may_place && node.placed < self.options.place_budget
+    }
+
+    fn dig_cost(&self, pos: BlockPosition) -> Option<u64> {
+        let state = self.block_at(pos)?;
+        let above = self.block_at(pos.with_diff(0, 1, 0))?;
+        if state.is_dangerous() || above.is_liquid() || above.is_falling() {
+            return None;
+        }
+
+        Some(self.options.dig_ticks(state)? as u64 * DIG_TICK_COST)
+    }
+}
+
+pub enum Progress {
+    Move(Step),
+    // The route continues into chunks that have not been received yet
+    Wait,
+    Arrived,
+    Stuck
+}
+
+// Travels toward a destination that may be far outside the loaded area. The
+// route is planned up to the edge of the loaded terrain and extended as the
+// chunks beyond it arrive.
+pub struct Journey {
+    dest: BlockPosition,
+    options: PathOptions,
+    steps: VecDeque<Step>,
+    // The step being performed, already taken off `steps`
+    current: Option<Step>,
+    // Blocks the bot digs or places itself, changing them needs no repair
+    own_changes: HashSet<BlockPosition>,
+    replan: bool,
+    interrupted: bool
+}
+
+impl Journey {
+    pub fn new(dest: BlockPosition, mut options: PathOptions) -> Self {
+        options.through_unloaded = true;
+        options.partial = true;
+        if options.max_nodes.is_none() {
+            options.max_nodes = Some(JOURNEY_MAX_NODES);
+        }
+
+        Journey {
+            dest,
+            options,
+            steps: VecDeque::default(),
+            current: None,
+            own_changes: HashSet::default(),
+            replan: true,
+            interrupted: false
+        }
+    }
+
+    pub fn dest(&self) -> BlockPosition {
+        self.dest
+    }
+
+    pub fn options(&self) -> &PathOptions {
+        &self.options
+    }
+
+    pub fn chunk_loaded(&mut self, addr: ChunkAddr) {
+        if self.steps.iter().any(|step| step.action == Action::Explore && step.position.chunk() == addr) {
+            self.replan = true;
+        }
+    }
+
+    pub fn replan(&mut self) {
+        self.replan = true;
+    }
+
+    // To be called before digging or placing a block, so the resulting block
+    // change isn't taken for an obstacle
+    pub fn expect_change(&mut self, block: BlockPosition) {
+        self.own_changes.insert(block);
+    }
+
+    // The step being performed can't be completed any more and should be
+    // abandoned, the next call to `next` plans a new route
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    // Patches the part of the route affected by block changes, falling back
+    // to planning from scratch when no detour rejoins it
+    pub fn blocks_changed(&mut self, gamestate: &GameState, position: BlockPosition, changed: &[BlockPosition]) {
+        let own_changes = &mut self.own_changes;
+        let changed: Vec<BlockPosition> = changed.iter()
+            .filter(|block| !own_changes.remove(block))
+            .cloned()
+            .collect();
+
+        if let Some(ref step) = self.current {
+            if changed.iter().any(|block| touches(step.position, *block)) {
+                self.interrupted = true;
+                self.replan = true;
+                return;
+            }
+        }
+
+        let first_affected = match self.steps.iter().position(|step| changed.iter().any(|block| touches(step.position, *block))) {
+            Some(idx) => idx,
+            None => return
+        };
+        let rejoin = (first_affected + REPAIR_WINDOW).min(self.steps.len() - 1);
+        if self.steps[rejoin].action == Action::Explore {
+            self.replan = true;
+            return;
+        }
+
+        // The detour starts where the step being performed ends
+        let start = self.current.as_ref().map_or(position, |step| step.position);
+        let mut options = self.options.clone();
+        options.partial = false;
+        options.max_nodes = Some(REPAIR_MAX_NODES);
+        match find_path(gamestate, start, &Exact(self.steps[rejoin].position), &options) {
+            Some(detour) => {
+                let rest = self.steps.split_off(rejoin + 1);
+                self.steps = detour.steps.into();
+                self.steps.extend(rest);
+            }
+            None => self.replan = true
+        }
+    }
+
+    pub fn next(&mut self, gamestate: &GameState, position: BlockPosition) -> Progress {
+        self.current = None;
+        self.interrupted = false;
+        let arrived = Near { pos: self.dest, radius: 2.0 };
+        if gamestate.is_chunk_loaded(self.dest.chunk()) && arrived.is_reached(&position) {
+            return Progress::Arrived;
+        }
+
+        if self.replan || self.steps.is_empty() {
+            self.replan = false;
+            let path = if gamestate.is_chunk_loaded(self.dest.chunk()) {
+                find_path(gamestate, position, &arrived, &self.options)
+            } else {
+                find_path(gamestate, position, &XZ { x: self.dest.x(), z: self.dest.z() }, &self.options)
+            };
+            self.steps = path.map_or(VecDeque::default(), |path| path.steps.into());
+        }
+
+        match self.steps.front() {
+            None => Progress::Stuck,
+            Some(step) if step.action == Action::Explore => Progress::Wait,
+            Some(_) => {
+                let step = self.steps.pop_front().unwrap();
+                self.current = Some(step.clone());
+                Progress::Move(step)
+            }
+        }
+    }
+}
+
+// Whether changing `block` can affect standing at `pos`
+fn touches(pos: BlockPosition, block: BlockPosition) -> bool {
+    (block.x() - pos.x()).abs() <= 1 && (block.z() - pos.z()).abs() <= 1 &&
+        block.y() >= pos.y() - 1 && block.y() <= pos.y() + 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use packets::ServerPacket;
+    use uuid::Uuid;
+
+    const STONE: BlockState = BlockState(1 << 4);
+    const WATER: BlockState = BlockState(9 << 4);
+    const LAVA: BlockState = BlockState(11 << 4);
+    const LADDER: BlockState = BlockState(65 << 4);
+    const VINE: BlockState = BlockState(106 << 4);
+    const BEDROCK: BlockState = BlockState(7 << 4);
+    const AIR: BlockState = BlockState(0);
+    const FIRE: BlockState = BlockState(51 << 4);
+    const CACTUS: BlockState = BlockState(81 << 4);
+    const MAGMA: BlockState = BlockState(213 << 4);
+    const ZOMBIE: i32 = 54;
+
+    // Nine chunks of stone floor at y = 64, standing in the middle of (0, 0)
+    fn flat() -> GameState {
+        let mut gamestate = GameState::with_chunks(Position::new(0.5, 65.0, 0.5), -1, -1, 1, 1);
+        gamestate.fill(BlockPosition::new(-16, 64, -16), BlockPosition::new(31, 64, 31), STONE);
+        gamestate
+    }
+
+    fn start() -> BlockPosition {
+        BlockPosition::new(0, 65, 0)
+    }
+
+    fn actions(path: &Path) -> Vec<Action> {
+        path.steps.iter().map(|step| step.action.clone()).collect()
+    }
+
+    #[test]
+    fn climbs_ladders_and_vines() {
+        for climbable in &[LADDER, VINE] {
+            let mut gamestate = flat();
+            gamestate.fill(BlockPosition::new(0, 65, 0), BlockPosition::new(0, 69, 0), *climbable);
+            gamestate.set_block_state(&BlockPosition::new(1, 69, 0), STONE);
+
+            let goal = Exact(BlockPosition::new(1, 70, 0));
+            let path = find_path(&gamestate, start(), &goal, &PathOptions::default()).unwrap();
+            assert!(path.complete);
+            assert_eq!(&actions(&path)[..4], &[Action::Climb, Action::Climb, Action::Climb, Action::Climb]);
+            assert_eq!(path.steps.last().unwrap().position, goal.0);
+
+            // Down again, a step off the side is cheaper past the first rung
+            let path = find_path(&gamestate, BlockPosition::new(0, 69, 0), &Exact(start()), &PathOptions::default()).unwrap();
+            assert_eq!(path.steps[0].action, Action::Climb);
+            assert_eq!(path.steps.last().unwrap().position, start());
+        }
+
+        let mut gamestate = flat();
+        gamestate.set_block_state(&BlockPosition::new(1, 69, 0), STONE);
+        assert!(find_path(&gamestate, start(), &Exact(BlockPosition::new(1, 70, 0)), &PathOptions::default()).is_none());
+    }
+
+    #[test]
+    fn swims_up_and_down() {
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(-2, 60, -2), BlockPosition::new(2, 64, 2), WATER);
+        gamestate.fill(BlockPosition::new(-2, 59, -2), BlockPosition::new(2, 59, 2), STONE);
+
+        let bottom = BlockPosition::new(1, 60, 1);
+        let path = find_path(&gamestate, start(), &Exact(bottom), &PathOptions::default()).unwrap();
+        // Stepping off into the pool is a fall into water
+        assert_eq!(path.steps[0].action, Action::Fall);
+        assert!(actions(&path)[1..].iter().all(|action| *action == Action::Swim));
+        assert_eq!(path.steps.last().unwrap().position, bottom);
+
+        let path = find_path(&gamestate, bottom, &Exact(BlockPosition::new(4, 65, 0)), &PathOptions::default()).unwrap();
+        assert!(actions(&path).contains(&Action::Swim));
+        assert_eq!(path.steps.last().unwrap().action, Action::Walk);
+    }
+
+    #[test]
+    fn swimming_costs_more_than_walking() {
+        // Walking around the pool takes two more steps than swimming through
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(1, 64, -1), BlockPosition::new(3, 64, 1), WATER);
+        gamestate.fill(BlockPosition::new(1, 63, -1), BlockPosition::new(3, 63, 1), STONE);
+
+        let path = find_path(&gamestate, start(), &Exact(BlockPosition::new(4, 65, 0)), &PathOptions::default()).unwrap();
+        assert!(!actions(&path).contains(&Action::Swim));
+    }
+
+    #[test]
+    fn never_enters_lava() {
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(2, 64, -16), BlockPosition::new(2, 64, 31), LAVA);
+        let goal = Exact(BlockPosition::new(4, 65, 0));
+        assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_none());
+
+        // Through a gap in the lava, however close to it
+        gamestate.set_block_state(&BlockPosition::new(2, 64, 5), STONE);
+        let path = find_path(&gamestate, start(), &goal, &PathOptions::default()).unwrap();
+        assert!(path.steps.iter().any(|step| step.position == BlockPosition::new(2, 65, 5)));
+        for step in &path.steps {
+            assert_eq!(gamestate.block_state_at(&step.position), Some(BlockState(0)));
+            assert_eq!(gamestate.block_state_at(&step.position.with_diff(0, -1, 0)), Some(STONE));
+        }
+    }
+
+    #[test]
+    fn digs_through_walls_when_allowed() {
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(2, 65, -16), BlockPosition::new(2, 66, 31), STONE);
+        let goal = Exact(BlockPosition::new(4, 65, 0));
+        assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_none());
+
+        let options = PathOptions {
+            may_dig: true,
+            ..PathOptions::default()
+        };
+        let path = find_path(&gamestate, start(), &goal, &options).unwrap();
+        let digs: Vec<&Step> = path.steps.iter()
+            .filter(|step| matches!(step.action, Action::Dig(_)))
+            .collect();
+        assert_eq!(digs.len(), 1);
+        let pos = digs[0].position;
+        assert_eq!(digs[0].action, Action::Dig(vec![pos, pos.with_diff(0, 1, 0)]));
+
+        // Unbreakable, or holding up water
+        gamestate.fill(BlockPosition::new(2, 65, -16), BlockPosition::new(2, 66, 31), BEDROCK);
+        assert!(find_path(&gamestate, start(), &goal, &options).is_none());
+        gamestate.fill(BlockPosition::new(2, 65, -16), BlockPosition::new(2, 66, 31), STONE);
+        gamestate.fill(BlockPosition::new(2, 67, -16), BlockPosition::new(2, 67, 31), WATER);
+        assert!(find_path(&gamestate, start(), &goal, &options).is_none());
+    }
+
+    #[test]
+    fn digs_faster_with_the_right_tool() {
+        let by_hand = PathOptions::default();
+        let pickaxe = PathOptions {
+            tools: vec![(Tool::Shovel, Some(ToolTier::Diamond)), (Tool::Pickaxe, Some(ToolTier::Iron))],
+            ..PathOptions::default()
+        };
+        assert!(pickaxe.dig_ticks(STONE).unwrap() < by_hand.dig_ticks(STONE).unwrap());
+        assert_eq!(pickaxe.dig_ticks(BEDROCK), None);
+        assert_eq!(pickaxe.dig_ticks(WATER), None);
+    }
+
+    #[test]
+    fn places_blocks_within_the_budget() {
+        let mut gamestate = flat();
+        for x in &[2, 4] {
+            gamestate.fill(BlockPosition::new(*x, 64, -16), BlockPosition::new(*x, 64, 31), AIR);
+        }
+        let goal = Exact(BlockPosition::new(6, 65, 0));
+        let with_budget = |place_budget| PathOptions {
+            may_place: true,
+            place_budget,
+            ..PathOptions::default()
+        };
+        assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_none());
+        assert!(find_path(&gamestate, start(), &goal, &with_budget(1)).is_none());
+
+        let path = find_path(&gamestate, start(), &goal, &with_budget(2)).unwrap();
+        let placed: Vec<BlockPosition> = path.steps.iter()
+            .filter_map(|step| if let Action::Place(block) = step.action { Some(block) } else { None })
+            .collect();
+        assert_eq!(placed.len(), 2);
+        assert_eq!(placed[0].x(), 2);
+        assert_eq!(placed[1].x(), 4);
+        assert!(placed.iter().all(|block| block.y() == 64));
+    }
+
+    #[test]
+    fn pillars_up_within_the_budget() {
+        let gamestate = flat();
+        let goal = Exact(BlockPosition::new(0, 68, 0));
+        let with_budget = |place_budget| PathOptions {
+            may_place: true,
+            place_budget,
+            ..PathOptions::default()
+        };
+        assert!(find_path(&gamestate, start(), &goal, &with_budget(2)).is_none());
+        let path = find_path(&gamestate, start(), &goal, &with_budget(3)).unwrap();
+        assert_eq!(actions(&path), vec![Action::Pillar; 3]);
+    }
+
+    #[test]
+    fn jumps_gaps_when_allowed() {
+        let parkour = PathOptions {
+            may_parkour: true,
+            ..PathOptions::default()
+        };
+        for gap in 1..=3 {
+            let mut gamestate = flat();
+            gamestate.fill(BlockPosition::new(1, 64, -16), BlockPosition::new(gap, 64, 31), AIR);
+            let goal = Exact(BlockPosition::new(gap + 2, 65, 0));
+            assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_none());
+
+            let path = find_path(&gamestate, start(), &goal, &parkour);
+            if gap > 2 {
+                assert!(path.is_none());
+                continue;
+            }
+            let path = path.unwrap();
+            assert_eq!(path.steps[0], Step { position: BlockPosition::new(gap + 1, 65, 0), action: Action::Parkour });
+            assert_eq!(path.steps.len(), 2);
+        }
+
+        // Not without head room for the jump
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(1, 64, -16), BlockPosition::new(1, 64, 31), AIR);
+        gamestate.fill(BlockPosition::new(-16, 67, -16), BlockPosition::new(31, 67, 31), STONE);
+        assert!(find_path(&gamestate, start(), &Exact(BlockPosition::new(3, 65, 0)), &parkour).is_none());
+    }
+
+    #[test]
+    fn walks_diagonally_around_corners_only() {
+        let gamestate = flat();
+        let goal = Exact(BlockPosition::new(3, 65, 3));
+        let path = find_path(&gamestate, start(), &goal, &PathOptions::default()).unwrap();
+        assert_eq!(path.steps.len(), 3);
+
+        // A pillar on one corner forces a step around it
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(1, 65, 0), BlockPosition::new(1, 66, 0), STONE);
+        let path = find_path(&gamestate, start(), &Exact(BlockPosition::new(1, 65, 1)), &PathOptions::default()).unwrap();
+        assert_eq!(path.steps.len(), 2);
+    }
+
+    #[test]
+    fn falls_as_far_as_health_allows() {
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(1, 64, -16), BlockPosition::new(31, 64, 31), AIR);
+        gamestate.fill(BlockPosition::new(1, 54, -16), BlockPosition::new(31, 54, 31), STONE);
+        let goal = Exact(BlockPosition::new(3, 55, 0));
+        assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_none());
+
+        let options = PathOptions {
+            max_fall: 20,
+            ..PathOptions::default()
+        };
+        let path = find_path(&gamestate, start(), &goal, &options).unwrap();
+        assert_eq!(path.steps[0], Step { position: BlockPosition::new(1, 55, 0), action: Action::Fall });
+
+        // Ten blocks would take three and a half hearts
+        gamestate.handle_packet(&ServerPacket::UpdateHealth { health: 7.0, food: 20, saturation: 5.0 }).unwrap();
+        assert!(find_path(&gamestate, start(), &goal, &options).is_none());
+
+        // Into water from any height
+        gamestate.fill(BlockPosition::new(1, 55, -1), BlockPosition::new(1, 55, 1), WATER);
+        let path = find_path(&gamestate, start(), &goal, &options).unwrap();
+        assert_eq!(path.steps[0], Step { position: BlockPosition::new(1, 55, 0), action: Action::Fall });
+    }
+
+    #[test]
+    fn gives_up_after_the_node_budget() {
+        let mut gamestate = flat();
+        // Walled in on every side
+        gamestate.fill(BlockPosition::new(-3, 65, -3), BlockPosition::new(3, 66, 3), STONE);
+        gamestate.fill(BlockPosition::new(-2, 65, -2), BlockPosition::new(2, 66, 2), AIR);
+        let goal = Exact(BlockPosition::new(20, 65, 0));
+        assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_none());
+
+        let limited = PathOptions {
+            may_dig: true,
+            max_nodes: Some(10),
+            ..PathOptions::default()
+        };
+        assert!(find_path(&gamestate, start(), &goal, &limited).is_none());
+
+        // The best partial path leads as close as the search got
+        let partial = PathOptions {
+            partial: true,
+            ..limited
+        };
+        let path = find_path(&gamestate, start(), &goal, &partial).unwrap();
+        assert!(!path.complete);
+        let end = path.steps.last().unwrap().position;
+        assert!(end.distance_to(&goal.0) < start().distance_to(&goal.0));
+
+        let unlimited = PathOptions {
+            may_dig: true,
+            ..PathOptions::default()
+        };
+        assert!(find_path(&gamestate, start(), &goal, &unlimited).unwrap().complete);
+    }
+
+    #[test]
+    fn gives_up_after_the_timeout() {
+        let gamestate = flat();
+        let goal = Exact(BlockPosition::new(30, 65, 30));
+        let options = |timeout| PathOptions {
+            timeout: Some(timeout),
+            partial: true,
+            ..PathOptions::default()
+        };
+        // Out of time before a single node was expanded
+        assert!(find_path(&gamestate, start(), &goal, &options(Duration::from_secs(0))).is_none());
+        assert!(find_path(&gamestate, start(), &goal, &options(Duration::from_secs(60))).unwrap().complete);
+    }
+
+    #[test]
+    fn paths_toward_every_goal() {
+        let gamestate = flat();
+        let goals: Vec<Box<dyn Goal>> = vec![
+            Box::new(Exact(BlockPosition::new(5, 65, -5))),
+            Box::new(Near { pos: BlockPosition::new(10, 65, 0), radius: 2.0 }),
+            Box::new(XZ { x: -7, z: 7 }),
+            Box::new(AnyOf(vec![BlockPosition::new(20, 65, 20), BlockPosition::new(-4, 65, 0)])),
+            Box::new(AwayFrom { pos: start(), distance: 6.0 }),
+            Box::new(Composite(vec![Box::new(XZ { x: 3, z: 3 }), Box::new(YLevel(65))]))
+        ];
+        for goal in &goals {
+            let path = find_path(&gamestate, start(), goal.as_ref(), &PathOptions::default()).unwrap();
+            assert!(path.complete);
+            assert!(goal.is_reached(&path.steps.last().unwrap().position));
+        }
+
+        // Already there
+        let path = find_path(&gamestate, start(), &YLevel(65), &PathOptions::default()).unwrap();
+        assert!(path.steps.is_empty());
+        assert!(find_path(&gamestate, start(), &YLevel(70), &PathOptions::default()).is_none());
+    }
+
+    #[test]
+    fn plans_into_unloaded_chunks() {
+        let gamestate = flat();
+        let goal = XZ { x: 100, z: 0 };
+        let options = PathOptions {
+            through_unloaded: true,
+            ..PathOptions::default()
+        };
+        let path = find_path(&gamestate, start(), &goal, &options).unwrap();
+        assert!(!path.complete);
+        let last = path.steps.last().unwrap();
+        assert_eq!(last.action, Action::Explore);
+        assert_eq!(last.position.chunk(), ChunkAddr::new(2, 0));
+        assert!(path.steps[..path.steps.len() - 1].iter().all(|step| gamestate.is_chunk_loaded(step.position.chunk())));
+
+        assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_none());
+    }
+
+    // Takes the steps the journey hands out until it wants something else
+    fn walk(journey: &mut Journey, gamestate: &mut GameState) -> Progress {
+        loop {
+            let position = gamestate.my_position().unwrap().block_position();
+            match journey.next(gamestate, position) {
+                Progress::Move(step) => {
+                    let pos = step.position;
+                    gamestate.set_my_position(Position::new(pos.x() as f64 + 0.5, pos.y() as f64, pos.z() as f64 + 0.5)).unwrap();
+                }
+                progress => return progress
+            }
+        }
+    }
+
+    #[test]
+    fn journey_continues_as_chunks_arrive() {
+        let mut gamestate = flat();
+        let mut journey = Journey::new(BlockPosition::new(40, 65, 0), PathOptions::default());
+        assert!(matches!(walk(&mut journey, &mut gamestate), Progress::Wait));
+        assert_eq!(gamestate.my_position().unwrap().block_position().chunk(), ChunkAddr::new(1, 0));
+
+        for z in -1..=1 {
+            gamestate.load_chunk_data(2, z, 0, &Bytes::new()).unwrap();
+            journey.chunk_loaded(ChunkAddr::new(2, z));
+        }
+        gamestate.fill(BlockPosition::new(32, 64, -16), BlockPosition::new(47, 64, 31), STONE);
+        assert!(matches!(walk(&mut journey, &mut gamestate), Progress::Arrived));
+        let end = gamestate.my_position().unwrap().block_position();
+        assert!(end.distance_to(&BlockPosition::new(40, 65, 0)) <= 2.0);
+    }
+
+    #[test]
+    fn journey_repairs_around_new_obstacles() {
+        let mut gamestate = flat();
+        let mut journey = Journey::new(BlockPosition::new(20, 65, 0), PathOptions::default());
+        journey.next(&gamestate, start());
+        let rejoin = journey.steps[REPAIR_WINDOW + 2].clone();
+
+        // Someone builds a wall across the route
+        let blocked = journey.steps[2].position;
+        let wall: Vec<BlockPosition> = (-2..=2)
+            .flat_map(|z| vec![blocked.with_diff(0, 0, z), blocked.with_diff(0, 1, z)])
+            .collect();
+        for block in &wall {
+            gamestate.set_block_state(block, STONE);
+        }
+        journey.blocks_changed(&gamestate, start(), &wall);
+        assert!(!journey.is_interrupted());
+        assert!(!journey.replan);
+        assert!(journey.steps.iter().all(|step| !wall.contains(&step.position)));
+        assert!(journey.steps.contains(&rejoin));
+        assert!(matches!(walk(&mut journey, &mut gamestate), Progress::Arrived));
+
+        // Changes the bot makes itself are expected
+        let mut journey = Journey::new(BlockPosition::new(20, 65, 0), PathOptions::default());
+        let first = match journey.next(&gamestate, start()) {
+            Progress::Move(step) => step,
+            _ => panic!("no first step")
+        };
+        let steps = journey.steps.clone();
+        let dug = steps[1].position.with_diff(0, -1, 0);
+        journey.expect_change(dug);
+        journey.blocks_changed(&gamestate, start(), &[dug]);
+        assert_eq!(journey.steps, steps);
+
+        // Under the step being taken there's nothing to repair
+        journey.blocks_changed(&gamestate, start(), &[first.position.with_diff(0, -1, 0)]);
+        assert!(journey.is_interrupted());
+        assert!(journey.replan);
+    }
+
+    // Whether standing at `pos` is next to `block` the way danger costs see it
+    fn is_next_to(pos: BlockPosition, block: BlockPosition) -> bool {
+        let (x, y, z) = (block.x() - pos.x(), block.y() - pos.y(), block.z() - pos.z());
+        (x == 0 && z == 0 && (y == -1 || y == 2)) || (x.abs() + z.abs() == 1 && (y == 0 || y == 1))
+    }
+
+    #[test]
+    fn keeps_away_from_hazards() {
+        let avoid = PathOptions {
+            costs: CostModel {
+                hazard_cost: None,
+                ..CostModel::default()
+            },
+            ..PathOptions::default()
+        };
+        let goal = Exact(BlockPosition::new(4, 65, 0));
+        for (hazard, block) in &[(FIRE, BlockPosition::new(2, 65, 0)), (CACTUS, BlockPosition::new(2, 65, 0)),
+                (MAGMA, BlockPosition::new(2, 64, 0))] {
+            let mut gamestate = flat();
+            gamestate.set_block_state(block, *hazard);
+            // Never into or onto it
+            let standing = if *hazard == MAGMA { block.with_diff(0, 1, 0) } else { *block };
+            let path = find_path(&gamestate, start(), &goal, &PathOptions::default()).unwrap();
+            assert!(path.steps.iter().all(|step| step.position != standing));
+            let path = find_path(&gamestate, start(), &goal, &avoid).unwrap();
+            assert!(path.steps.iter().all(|step| !is_next_to(step.position, *block)));
+        }
+
+        // Along a corridor with fire in the wall there's no way around it
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(-16, 65, -1), BlockPosition::new(31, 66, 1), STONE);
+        gamestate.fill(BlockPosition::new(-16, 65, 0), BlockPosition::new(31, 66, 0), AIR);
+        gamestate.set_block_state(&BlockPosition::new(2, 65, 1), FIRE);
+        assert!(find_path(&gamestate, start(), &goal, &PathOptions::default()).is_some());
+        assert!(find_path(&gamestate, start(), &goal, &avoid).is_none());
+    }
+
+    #[test]
+    fn weighs_fall_damage() {
+        // A five block cliff, with a ledge halfway down further along it
+        let mut gamestate = flat();
+        gamestate.fill(BlockPosition::new(1, 64, -16), BlockPosition::new(31, 64, 31), AIR);
+        gamestate.fill(BlockPosition::new(1, 59, -16), BlockPosition::new(31, 59, 31), STONE);
+        gamestate.set_block_state(&BlockPosition::new(1, 61, 6), STONE);
+        let goal = Exact(BlockPosition::new(3, 60, 0));
+        let options = |fall_damage_cost| PathOptions {
+            max_fall: 20,
+            costs: CostModel {
+                fall_damage_cost,
+                ..CostModel::default()
+            },
+            ..PathOptions::default()
+        };
+
+        let path = find_path(&gamestate, start(), &goal, &options(20)).unwrap();
+        assert_eq!(path.steps[0], Step { position: BlockPosition::new(1, 60, 0), action: Action::Fall });
+
+        let path = find_path(&gamestate, start(), &goal, &options(1000)).unwrap();
+        assert!(path.steps.iter().any(|step| step.position == BlockPosition::new(1, 62, 6)));
+        let mut from = start();
+        for step in &path.steps {
+            assert!(from.y() - step.position.y() <= SAFE_FALL as i32);
+            from = step.position;
+        }
+    }
+
+    #[test]
+    fn keeps_away_from_hostile_mobs() {
+        let mut gamestate = flat();
+        gamestate.handle_packet(&ServerPacket::SpawnMob {
+            entity_id: 2,
+            uuid: Uuid::nil(),
+            mob_type: ZOMBIE,
+            x: 7.5,
+            y: 65.0,
+            z: 0.5,
+            yaw: 0,
+            pitch: 0,
+            head_pitch: 0,
+            velocity_x: 0,
+            velocity_y: 0,
+            velocity_z: 0
+        }).unwrap();
+        let zombie = Position::new(7.5, 65.0, 0.5);
+        let goal = Exact(BlockPosition::new(14, 65, 0));
+        let too_close = |path: &Path| path.steps.iter()
+            .filter(|step| {
+                let pos = step.position;
+                zombie.distance_to(&Position::new(pos.x() as f64 + 0.5, pos.y() as f64, pos.z() as f64 + 0.5)) <= 6.0
+            })
+            .count();
+
+        let straight = find_path(&gamestate, start(), &goal, &PathOptions {
+            costs: CostModel {
+                mob_cost: Some(0),
+                ..CostModel::default()
+            },
+            ..PathOptions::default()
+        }).unwrap();
+        let cautious = find_path(&gamestate, start(), &goal, &PathOptions::default()).unwrap();
+        assert!(too_close(&cautious) < too_close(&straight));
+
+        let avoid = PathOptions {
+            costs: CostModel {
+                mob_cost: None,
+                ..CostModel::default()
+            },
+            ..PathOptions::default()
+        };
+        let path = find_path(&gamestate, start(), &goal, &avoid).unwrap();
+        assert_eq!(too_close(&path), 0);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::geom::{BlockPosition, Distance};
+
+pub trait Goal {
+    fn is_reached(&self, pos: &BlockPosition) -> bool;
+
+    // Estimated remaining distance in blocks. The search weighs it like
+    // walking, and heavier still through unloaded terrain, so the paths it
+    // finds are good but not always the shortest.
+    fn heuristic(&self, pos: &BlockPosition) -> Distance;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Exact(pub BlockPosition);
+
+impl Goal for Exact {
+    fn is_reached(&self, pos: &BlockPosition) -> bool {
+        *pos == self.0
+    }
+
+    fn heuristic(&self, pos: &BlockPosition) -> Distance {
+        pos.distance_to(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Near {
+    pub pos: BlockPosition,
+    pub radius: Distance
+}
+
+impl Goal for Near {
+    fn is_reached(&self, pos: &BlockPosition) -> bool {
+        pos.distance_to(&self.pos) <= self.radius
+    }
+
+    fn heuristic(&self, pos: &BlockPosition) -> Distance {
+        (pos.distance_to(&self.pos) - self.radius).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct XZ {
+    pub x: i32,
+    pub z: i32
+}
+
+impl Goal for XZ {
+    fn is_reached(&self, pos: &BlockPosition) -> bool {
+        pos.x() == self.x && pos.z() == self.z
+    }
+
+    fn heuristic(&self, pos: &BlockPosition) -> Distance {
+        let dx = (pos.x() - self.x) as Distance;
+        let dz = (pos.z() - self.z) as Distance;
+        (dx * dx + dz * dz).sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct YLevel(pub i32);
+
+impl Goal for YLevel {
+    fn is_reached(&self, pos: &BlockPosition) -> bool {
+        pos.y() == self.0
+    }
+
+    fn heuristic(&self, pos: &BlockPosition) -> Distance {
+        (pos.y() - self.0).abs() as Distance
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnyOf(pub Vec<BlockPosition>);
+
+impl Goal for AnyOf {
+    fn is_reached(&self, pos: &BlockPosition) -> bool {
+        self.0.contains(pos)
+    }
+
+    fn heuristic(&self, pos: &BlockPosition) -> Distance {
+        self.0.iter()
+            .map(|p| pos.distance_to(p))
+            .fold(Distance::INFINITY, Distance::min)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AwayFrom {
+    pub pos: BlockPosition,
+    pub distance: Distance
+}
+
+impl Goal for AwayFrom {
+    fn is_reached(&self, pos: &BlockPosition) -> bool {
+        pos.distance_to(&self.pos) >= self.distance
+    }
+
+    fn heuristic(&self, pos: &BlockPosition) -> Distance {
+        (self.distance - pos.distance_to(&self.pos)).max(0.0)
+    }
+}
+
+// Reached only once every inner goal is
+pub struct Composite(pub Vec<Box<dyn Goal>>);
+
+impl Goal for Composite {
+    fn is_reached(&self, pos: &BlockPosition) -> bool {
+        self.0.iter().all(|goal| goal.is_reached(pos))
+    }
+
+    fn heuristic(&self, pos: &BlockPosition) -> Distance {
+        self.0.iter()
+            .map(|goal| goal.heuristic(pos))
+            .fold(0.0, Distance::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: i32, y: i32, z: i32) -> BlockPosition {
+        BlockPosition::new(x, y, z)
+    }
+
+    #[test]
+    fn exact() {
+        let goal = Exact(at(1, 2, 3));
+        assert!(goal.is_reached(&at(1, 2, 3)));
+        assert!(!goal.is_reached(&at(1, 3, 3)));
+        assert_eq!(goal.heuristic(&at(1, 2, 3)), 0.0);
+        assert_eq!(goal.heuristic(&at(4, 6, 3)), 5.0);
+    }
+
+    #[test]
+    fn near() {
+        let goal = Near { pos: at(0, 64, 0), radius: 2.0 };
+        assert!(goal.is_reached(&at(0, 64, 0)));
+        assert!(goal.is_reached(&at(0, 66, 0)));
+        assert!(!goal.is_reached(&at(2, 65, 0)));
+        assert_eq!(goal.heuristic(&at(1, 64, 1)), 0.0);
+        assert_eq!(goal.heuristic(&at(5, 64, 0)), 3.0);
+    }
+
+    #[test]
+    fn xz() {
+        let goal = XZ { x: -3, z: 4 };
+        assert!(goal.is_reached(&at(-3, 0, 4)));
+        assert!(goal.is_reached(&at(-3, 255, 4)));
+        assert!(!goal.is_reached(&at(-3, 64, 5)));
+        assert_eq!(goal.heuristic(&at(0, 10, 0)), 5.0);
+    }
+
+    #[test]
+    fn y_level() {
+        let goal = YLevel(12);
+        assert!(goal.is_reached(&at(100, 12, -100)));
+        assert!(!goal.is_reached(&at(0, 13, 0)));
+        assert_eq!(goal.heuristic(&at(0, 64, 0)), 52.0);
+        assert_eq!(goal.heuristic(&at(0, 2, 0)), 10.0);
+    }
+
+    #[test]
+    fn any_of() {
+        let goal = AnyOf(vec![at(10, 64, 0), at(0, 64, -3)]);
+        assert!(goal.is_reached(&at(10, 64, 0)));
+        assert!(goal.is_reached(&at(0, 64, -3)));
+        assert!(!goal.is_reached(&at(0, 64, 0)));
+        assert_eq!(goal.heuristic(&at(0, 64, 0)), 3.0);
+
+        // Nothing to reach
+        let goal = AnyOf(Vec::new());
+        assert!(!goal.is_reached(&at(0, 64, 0)));
+        assert_eq!(goal.heuristic(&at(0, 64, 0)), Distance::INFINITY);
+    }
+
+    #[test]
+    fn away_from() {
+        let goal = AwayFrom { pos: at(0, 64, 0), distance: 10.0 };
+        assert!(!goal.is_reached(&at(0, 64, 0)));
+        assert!(goal.is_reached(&at(6, 64, 8)));
+        assert!(goal.is_reached(&at(0, 80, 0)));
+        assert_eq!(goal.heuristic(&at(0, 64, 4)), 6.0);
+        assert_eq!(goal.heuristic(&at(0, 64, 40)), 0.0);
+    }
+
+    #[test]
+    fn composite() {
+        let goal = Composite(vec![Box::new(XZ { x: 0, z: 0 }), Box::new(YLevel(70))]);
+        assert!(goal.is_reached(&at(0, 70, 0)));
+        assert!(!goal.is_reached(&at(0, 64, 0)));
+        assert!(!goal.is_reached(&at(1, 70, 0)));
+        assert_eq!(goal.heuristic(&at(3, 64, 4)), 6.0);
+        assert_eq!(goal.heuristic(&at(3, 70, 4)), 5.0);
+
+        // Trivially reached without any goals
+        let goal = Composite(Vec::new());
+        assert!(goal.is_reached(&at(0, 64, 0)));
+        assert_eq!(goal.heuristic(&at(0, 64, 0)), 0.0);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{Error, MinebotClient, Result};
+use crate::connect::ConnectOptions;
+use crate::events::{Event, EventMatcher, EventMatchers};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub factor: u32,
+    // Give up after this many failed attempts in a row
+    pub max_attempts: Option<u32>
+}
+
+impl Backoff {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let mut delay = self.initial;
+        for _ in 1..attempt {
+            delay *= self.factor;
+            if delay >= self.max {
+                return self.max;
+            }
+        }
+        delay.min(self.max)
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            factor: 2,
+            max_attempts: None
+        }
+    }
+}
+
+// Keeps a bot online across server restarts and network trouble. Every
+// reconnect starts over with a fresh GameState, the registered matchers are
+// kept.
+pub struct ReconnectingClient {
+    host: String,
+    port: u16,
+    username: String,
+    options: ConnectOptions,
+    backoff: Backoff,
+    matchers: EventMatchers,
+    client: Option<MinebotClient>
+}
+
+impl ReconnectingClient {
+    pub fn connect(host: String, port: u16, username: String, options: ConnectOptions, backoff: Backoff) -> Result<Self> {
+        let client = MinebotClient::connect_with(host.clone(), port, username.clone(), &options)?;
+        Ok(ReconnectingClient {
+            host,
+            port,
+            username,
+            options,
+            backoff,
+            matchers: EventMatchers::default(),
+            client: Some(client)
+        })
+    }
+
+    pub fn listen(&mut self, matcher: EventMatcher) {
+        self.matchers.listen(matcher);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.is_some()
+    }
+
+    pub fn client(&self) -> Result<&MinebotClient> {
+        self.client.as_ref().ok_or_else(not_connected)
+    }
+
+    // Connection errors from calls made through this should be passed to
+    // `connection_lost`, so the next poll reconnects
+    pub fn client_mut(&mut self) -> Result<&mut MinebotClient> {
+        self.client.as_mut().ok_or_else(not_connected)
+    }
+
+    // Returns ConnectionLost when the connection drops and Reconnected once it
+    // is back, besides the events of the registered matchers
+    pub fn poll_event(&mut self) -> Result<Event> {
+        let result = match self.client {
+            Some(ref mut client) => client.poll_until_event(&self.matchers),
+            None => return self.reconnect()
+        };
+        match result {
+            Err(ref err) if err.is_connection_error() => Ok(self.connection_lost(err)),
+            res => res
+        }
+    }
+
+    pub fn connection_lost(&mut self, err: &Error) -> Event {
+        warn!("Lost connection to {}:{}: {}", self.host, self.port, err);
+        if let Some(mut client) = self.client.take() {
+            let _ = client.disconnect();
+        }
+        Event::ConnectionLost {
+            reason: err.to_string()
+        }
+    }
+
+    fn reconnect(&mut self) -> Result<Event> {
+        let mut attempt = 1;
+        loop {
+            let delay = self.backoff.delay(attempt);
+            info!("Reconnecting in {} ms (attempt {})", delay.as_millis(), attempt);
+            thread::sleep(delay);
+
+            match MinebotClient::connect_with(self.host.clone(), self.port, self.username.clone(), &self.options) {
+                Ok(client) => {
+                    self.client = Some(client);
+                    return Ok(Event::Reconnected {
+                        attempts: attempt
+                    });
+                }
+                Err(ref err) if err.is_connection_error() && self.backoff.max_attempts.is_none_or(|max| attempt < max) => {
+                    warn!("Reconnect failed: {}", err);
+                    attempt += 1;
+                }
+                Err(err) => return Err(err)
+            }
+        }
+    }
+}
+
+fn not_connected() -> Error {
+    Error::Disconnected("not connected".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_server::FakeServer;
+    use std::sync::mpsc;
+    use std::time::Instant;
+
+    fn backoff(max_attempts: Option<u32>) -> Backoff {
+        Backoff {
+            initial: Duration::from_millis(20),
+            max: Duration::from_millis(80),
+            factor: 2,
+            max_attempts
+        }
+    }
+
+    fn options() -> ConnectOptions {
+        ConnectOptions::new()
+            .connect_timeout(Duration::from_secs(5))
+            .silence_timeout(Duration::from_secs(5))
+    }
+
+    #[test]
+    fn backoff_schedule() {
+        let backoff = backoff(None);
+        let delays: Vec<_> = (1..=6).map(|attempt| backoff.delay(attempt).as_millis()).collect();
+        assert_eq!(delays, [20, 40, 80, 80, 80, 80]);
+
+        let backoff = Backoff::default();
+        assert_eq!(backoff.delay(1), Duration::from_secs(1));
+        assert_eq!(backoff.delay(6), Duration::from_secs(32));
+        assert_eq!(backoff.delay(7), Duration::from_secs(60));
+        assert_eq!(backoff.delay(1000), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn reconnects_after_the_server_drops() {
+        let server = FakeServer::bind();
+        let port = server.port();
+        let (done, finished) = mpsc::channel::<()>();
+        let server = thread::spawn(move || {
+            drop(server.accept());
+            let mut conn = server.accept();
+            conn.chat("alex", "welcome back");
+            let _ = finished.recv();
+        });
+
+        let mut client = ReconnectingClient::connect("127.0.0.1".to_owned(), port, "bot".to_owned(), options(), backoff(Some(3))).unwrap();
+        client.listen(EventMatcher::ChatMessage);
+        assert!(client.is_connected());
+
+        match client.poll_event().unwrap() {
+            Event::ConnectionLost { .. } => (),
+            event => panic!("expected ConnectionLost, got {:?}", event)
+        }
+        assert!(!client.is_connected());
+        assert!(client.client().is_err());
+
+        match client.poll_event().unwrap() {
+            Event::Reconnected { attempts } => assert_eq!(attempts, 1),
+            event => panic!("expected Reconnected, got {:?}", event)
+        }
+        assert!(client.is_connected());
+
+        match client.poll_event().unwrap() {
+            Event::ChatMessage { player, message } => assert_eq!((player.as_ref(), message.as_ref()), ("alex", "welcome back")),
+            event => panic!("expected ChatMessage, got {:?}", event)
+        }
+        done.send(()).unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let server = FakeServer::bind();
+        let port = server.port();
+        let server = thread::spawn(move || {
+            // Logs in once, then the server goes away for good
+            drop(server.accept());
+        });
+
+        let mut client = ReconnectingClient::connect("127.0.0.1".to_owned(), port, "bot".to_owned(), options(), backoff(Some(3))).unwrap();
+        server.join().unwrap();
+        match client.poll_event().unwrap() {
+            Event::ConnectionLost { .. } => (),
+            event => panic!("expected ConnectionLost, got {:?}", event)
+        }
+
+        let started = Instant::now();
+        let err = client.poll_event().unwrap_err();
+        assert!(err.is_connection_error(), "{}", err);
+        // Three attempts, sleeping 20, 40 and 80 ms before them
+        assert!(started.elapsed() >= Duration::from_millis(140));
+        assert!(!client.is_connected());
+    }
+}
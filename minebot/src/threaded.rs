@@ -0,0 +1,319 @@
+use crate::{acknowledgement, position_packet, responses, Error, MinebotClient, Result};
+use crate::blocks::{BlockQuery, BlockState};
+use crate::clock::Clock;
+use crate::events::{Event, EventMatchers};
+use crate::gamestate::GameState;
+use crate::geom::{BlockPosition, Position};
+use crate::path::{Goal, Path, PathOptions};
+use crate::watchdog::Watchdog;
+use nbt::codec::{CodecError, NbtCodec, PacketEncoder};
+use packets::{ClientPacket, DimensionId, ServerPacket};
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{Shutdown, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+struct Writer {
+    sock: TcpStream,
+    encoder: PacketEncoder
+}
+
+impl Writer {
+    fn send(&mut self, packet: ClientPacket) -> Result<()> {
+        trace!("Sending: {:?}", packet);
+        self.encoder.send(&mut self.sock, &packet).map_err(CodecError::Io)?;
+        Ok(())
+    }
+}
+
+// A client whose connection is serviced by a background thread, so keepalives
+// and teleports are answered even while the caller is busy. Events matched by
+// the thread are delivered over a channel.
+pub struct ThreadedClient {
+    gamestate: Arc<Mutex<GameState>>,
+    clock: Arc<Mutex<Clock>>,
+    writer: Arc<Mutex<Writer>>,
+    events: Receiver<Event>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>
+}
+
+impl ThreadedClient {
+    pub fn spawn(client: MinebotClient, matchers: EventMatchers) -> Result<Self> {
+        let MinebotClient { sock, mut codec, gamestate, clock, watchdog } = client;
+        let gamestate = Arc::new(Mutex::new(gamestate));
+        let clock = Arc::new(Mutex::new(clock));
+        let writer = Arc::new(Mutex::new(Writer {
+            sock: sock.try_clone()?,
+            encoder: codec.take_encoder()
+        }));
+        let running = Arc::new(AtomicBool::new(true));
+        let (sender, events) = mpsc::channel();
+
+        let network = Network {
+            sock,
+            codec,
+            gamestate: gamestate.clone(),
+            clock: clock.clone(),
+            writer: writer.clone(),
+            watchdog,
+            matchers,
+            pending: VecDeque::new(),
+            events: sender,
+            running: running.clone()
+        };
+        let thread = thread::Builder::new()
+            .name("minebot-network".to_owned())
+            .spawn(move || network.run())?;
+
+        Ok(ThreadedClient {
+            gamestate,
+            clock,
+            writer,
+            events,
+            running,
+            thread: Some(thread)
+        })
+    }
+
+    // Blocks until the next event, fails once the connection is gone
+    pub fn next_event(&self) -> Result<Event> {
+        self.events.recv().map_err(|_| Error::Disconnected("network thread stopped".to_owned()))
+    }
+
+    pub fn try_next_event(&self) -> Result<Option<Event>> {
+        match self.events.try_recv() {
+            Ok(event) => Ok(Some(event)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(Error::Disconnected("network thread stopped".to_owned()))
+        }
+    }
+
+    pub fn events(&self) -> &Receiver<Event> {
+        &self.events
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn send(&self, packet: ClientPacket) -> Result<()> {
+        lock(&self.writer).send(packet)
+    }
+
+    pub fn say<M: Into<String>>(&self, msg: M) -> Result<()> {
+        self.send(ClientPacket::ChatMessage { message: msg.into() })
+    }
+
+    pub fn set_my_position(&self, position: Position) -> Result<()> {
+        let packet = {
+            let mut gamestate = lock(&self.gamestate);
+            gamestate.set_my_position(position)?;
+            position_packet(&gamestate)?
+        };
+        self.send(packet)
+    }
+
+    pub fn current_tick(&self) -> i64 {
+        lock(&self.clock).current_tick()
+    }
+
+    pub fn health(&self) -> f32 {
+        lock(&self.gamestate).health()
+    }
+
+    pub fn food(&self) -> f32 {
+        lock(&self.gamestate).food()
+    }
+
+    pub fn my_position(&self) -> Result<Position> {
+        lock(&self.gamestate).my_position().copied()
+    }
+
+    pub fn dimension(&self) -> DimensionId {
+        lock(&self.gamestate).dimension()
+    }
+
+    pub fn last_death(&self) -> Option<(DimensionId, Position)> {
+        lock(&self.gamestate).last_death()
+    }
+
+    pub fn player_names(&self) -> Vec<String> {
+        lock(&self.gamestate).player_names().into_iter()
+            .map(|name| name.to_owned())
+            .collect()
+    }
+
+    pub fn block_state_at(&self, position: &BlockPosition) -> Option<BlockState> {
+        lock(&self.gamestate).block_state_at(position)
+    }
+
+    pub fn find_blocks_matching(&self, query: &BlockQuery, position: &BlockPosition, distance: i32) -> Vec<BlockPosition> {
+        lock(&self.gamestate).find_blocks_within(|bs| query.matches(bs), position, distance)
+    }
+
+    // Holds the game state for the whole search, the network thread keeps
+    // answering keepalives and teleports meanwhile and applies the other
+    // packets once the search is done
+    pub fn find_path_with(&self, start: BlockPosition, goal: &dyn Goal, options: &PathOptions) -> Option<Path> {
+        lock(&self.gamestate).find_path_with(start, goal, options)
+    }
+
+    pub fn disconnect(&mut self) -> Result<()> {
+        self.running.store(false, Ordering::SeqCst);
+        let res = match lock(&self.writer).sock.shutdown(Shutdown::Both) {
+            Err(ref e) if e.kind() == ErrorKind::NotConnected => Ok(()),
+            res => res
+        };
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        Ok(res?)
+    }
+}
+
+impl Drop for ThreadedClient {
+    fn drop(&mut self) {
+        let _ = self.disconnect();
+    }
+}
+
+struct Network {
+    sock: TcpStream,
+    codec: NbtCodec,
+    gamestate: Arc<Mutex<GameState>>,
+    clock: Arc<Mutex<Clock>>,
+    writer: Arc<Mutex<Writer>>,
+    watchdog: Watchdog,
+    matchers: EventMatchers,
+    // Packets waiting for the caller to let go of the game state
+    pending: VecDeque<ServerPacket>,
+    events: Sender<Event>,
+    running: Arc<AtomicBool>
+}
+
+impl Network {
+    fn run(mut self) {
+        while self.running.load(Ordering::SeqCst) {
+            match self.step() {
+                Ok(()) => {}
+                Err(ref err) if !err.is_connection_error() => warn!("Ignoring packet: {}", err),
+                Err(err) => {
+                    if self.running.load(Ordering::SeqCst) {
+                        warn!("Network thread stopping: {}", err);
+                        let _ = self.events.send(Event::ConnectionLost {
+                            reason: err.to_string()
+                        });
+                    }
+                    break;
+                }
+            }
+        }
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    fn step(&mut self) -> Result<()> {
+        let until = {
+            let mut clock = lock(&self.clock);
+            clock.advance();
+            clock.current_tick_end()
+        };
+        let packet = self.codec.receive_timeout(&mut self.sock, until)?;
+        if let Some(packet) = packet {
+            self.handle(packet)?;
+        }
+        self.apply_pending()?;
+        if self.watchdog.expired() {
+            return Err(Error::Timeout);
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, packet: ServerPacket) -> Result<()> {
+        self.watchdog.handle_packet(&packet);
+        lock(&self.clock).handle_packet(&packet);
+        // Answered before taking the game state, which the caller may be holding
+        if let Some(ack) = acknowledgement(&packet) {
+            lock(&self.writer).send(ack)?;
+        }
+        self.pending.push_back(packet);
+        Ok(())
+    }
+
+    fn apply_pending(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let mut gamestate = match self.gamestate.try_lock() {
+            Ok(gamestate) => gamestate,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return Ok(())
+        };
+        while let Some(packet) = self.pending.pop_front() {
+            let event = self.matchers.match_packet(&packet, &gamestate);
+            gamestate.handle_packet(&packet)?;
+            let mut writer = lock(&self.writer);
+            for response in responses(&packet, &gamestate)? {
+                writer.send(response)?;
+            }
+            if let Some(event) = event {
+                // Nobody is listening any more, keep the connection alive regardless
+                let _ = self.events.send(event);
+            }
+        }
+        Ok(())
+    }
+}
+
+// A panic on the other side doesn't make the data unusable
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connect::ConnectOptions;
+    use crate::events::EventMatcher;
+    use crate::fake_server::{FakeServer, CLIENT_KEEP_ALIVE, CLIENT_POSITION_AND_LOOK, CLIENT_TELEPORT_CONFIRM};
+    use std::time::Duration;
+
+    #[test]
+    fn answers_keepalives_while_the_game_state_is_held() {
+        let server = FakeServer::bind();
+        let port = server.port();
+        let server = thread::spawn(move || server.accept());
+        let options = ConnectOptions::new().silence_timeout(Duration::from_secs(5));
+        let client = MinebotClient::connect_with("127.0.0.1".to_owned(), port, "bot".to_owned(), &options).unwrap();
+        let mut matchers = EventMatchers::default();
+        matchers.listen(EventMatcher::ChatMessage);
+        let client = client.into_threaded(matchers).unwrap();
+        let mut conn = server.join().unwrap();
+        conn.join_game(7);
+        conn.teleport(0.5, 64.0, 0.5, 1);
+        conn.expect(CLIENT_POSITION_AND_LOOK);
+
+        let gamestate = lock(&client.gamestate);
+        conn.keep_alive(42);
+        assert_eq!(conn.expect(CLIENT_KEEP_ALIVE), 42i64.to_be_bytes());
+        conn.teleport(10.5, 70.0, -3.5, 2);
+        assert_eq!(conn.expect(CLIENT_TELEPORT_CONFIRM), [2]);
+        conn.chat("alex", "hi");
+        conn.keep_alive(43);
+        assert_eq!(conn.expect(CLIENT_KEEP_ALIVE), 43i64.to_be_bytes());
+        // Nothing was applied while the game state was held
+        assert_eq!(*gamestate.my_position().unwrap(), Position::new(0.5, 64.0, 0.5));
+        assert!(client.try_next_event().unwrap().is_none());
+        drop(gamestate);
+
+        match client.next_event().unwrap() {
+            Event::ChatMessage { player, message } => assert_eq!((player.as_ref(), message.as_ref()), ("alex", "hi")),
+            event => panic!("expected ChatMessage, got {:?}", event)
+        }
+        assert_eq!(client.my_position().unwrap(), Position::new(10.5, 70.0, -3.5));
+        conn.expect(CLIENT_POSITION_AND_LOOK);
+    }
+}
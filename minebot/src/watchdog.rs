@@ -0,0 +1,44 @@
+use packets::ServerPacket;
+use std::time::{Duration, Instant};
+
+// Notices a connection that went quiet without being closed, which a
+// blocking read would otherwise wait on forever
+pub struct Watchdog {
+    last_packet: Instant,
+    last_keepalive: Instant,
+    silence_timeout: Duration,
+    keepalive_timeout: Duration
+}
+
+impl Watchdog {
+    pub fn new(silence_timeout: Duration, keepalive_timeout: Duration) -> Self {
+        let now = Instant::now();
+        Watchdog {
+            last_packet: now,
+            last_keepalive: now,
+            silence_timeout,
+            keepalive_timeout
+        }
+    }
+
+    pub fn handle_packet(&mut self, packet: &ServerPacket) {
+        let now = Instant::now();
+        self.last_packet = now;
+        if let ServerPacket::KeepAlive { .. } = packet {
+            self.last_keepalive = now;
+        }
+    }
+
+    pub fn expired(&self) -> bool {
+        let now = Instant::now();
+        if now - self.last_packet > self.silence_timeout {
+            warn!("No packets received for {} ms", (now - self.last_packet).as_millis());
+            true
+        } else if now - self.last_keepalive > self.keepalive_timeout {
+            warn!("No keepalive received for {} ms", (now - self.last_keepalive).as_millis());
+            true
+        } else {
+            false
+        }
+    }
+}
\ No newline at end of file
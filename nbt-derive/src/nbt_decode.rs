@@ -56,7 +56,7 @@ impl quote::ToTokens for NbtDecodeReceiver {
                         let variant_name = quote!(#ident::#variant_ident);
                         let new_val = build_struct(variant_name, &variant.fields);
                         quote! {
-                            #ordinal => #new_val
+                            #ordinal => Ok(#new_val)
                         }
                     })
                     .collect();
@@ -64,11 +64,11 @@ impl quote::ToTokens for NbtDecodeReceiver {
 
                 quote! {
                     impl #generics _nbt::NbtDecode for #ident #generics {
-                        fn decode(buf: &mut Bytes) -> Self {
-                            let ordinal = _nbt::NbtDecoder::decode(&_nbt::VarNum, buf);
+                        fn decode(buf: &mut Bytes) -> _nbt::DecodeResult<Self> {
+                            let ordinal = _nbt::NbtDecoder::decode(&_nbt::VarNum, buf)?;
                             match ordinal {
                                 #(#match_arms),*,
-                                _ => panic!("Unrecognized ordinal {:02X}", ordinal)
+                                _ => Err(_nbt::DecodeError::InvalidValue(stringify!(#ident), ordinal as i64))
                             }
                         }
                     }
@@ -80,8 +80,8 @@ impl quote::ToTokens for NbtDecodeReceiver {
 
                 quote! {
                     impl #generics _nbt::NbtDecode for #ident #generics {
-                        fn decode(buf: &mut Bytes) -> Self {
-                            #new_val
+                        fn decode(buf: &mut Bytes) -> _nbt::DecodeResult<Self> {
+                            Ok(#new_val)
                         }
                     }
                 }
@@ -124,8 +124,8 @@ fn build_decode_field(field: &NbtDecodeFieldReceiver) -> quote::Tokens {
     match field.codec {
         Codec::Default => {
             let ty = &field.ty;
-            quote!(<#ty>::decode(buf))
+            quote!(<#ty>::decode(buf)?)
         },
-        Codec::VarNum => quote!(_nbt::NbtDecoder::decode(&_nbt::VarNum, buf))
+        Codec::VarNum => quote!(_nbt::NbtDecoder::decode(&_nbt::VarNum, buf)?)
     }
 }
\ No newline at end of file
@@ -1,30 +1,56 @@
-use bytes::{BufMut, BytesMut};
-use crate::{NbtDecode, NbtEncode, NbtEncoder, VarNum};
-use std::io::{ErrorKind, Read, Result, Write};
+use bytes::BytesMut;
+use crate::{DecodeError, DecodeResult, NbtDecode, NbtEncode, NbtEncoder, VarNum};
+use std::io::{self, ErrorKind, Read, Write};
+use std::mem;
 use std::net::TcpStream;
 use std::time::Instant;
 
+quick_error! {
+    #[derive(Debug)]
+    pub enum CodecError {
+        Io(err: io::Error) {
+            description(err.description())
+            display("{}", err)
+            cause(err)
+            from()
+        }
+        Decode(err: DecodeError) {
+            description(err.description())
+            display("{}", err)
+            cause(err)
+            from()
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, CodecError>;
+
+// Most a single read takes off the socket while waiting for a packet
+const READ_CHUNK: usize = 16 * 1024;
+
 #[derive(Clone)]
 pub struct NbtCodec {
     incoming: BytesMut,
-    outgoing: BytesMut
+    encoder: PacketEncoder
 }
 
 impl NbtCodec {
     pub fn new() -> Self {
         NbtCodec {
             incoming: BytesMut::with_capacity(1024 * 1024),
-            outgoing: BytesMut::with_capacity(1024 * 1024)
+            encoder: PacketEncoder { outgoing: BytesMut::with_capacity(1024 * 1024) }
         }
     }
 
-    pub fn send<W, P>(&mut self, mut out: W, packet: P) -> Result<()>
+    // Hands the sending half to whoever writes to the stream, leaving an
+    // empty one behind
+    pub fn take_encoder(&mut self) -> PacketEncoder {
+        mem::replace(&mut self.encoder, PacketEncoder::new())
+    }
+
+    pub fn send<W, P>(&mut self, out: W, packet: P) -> io::Result<()>
         where W: Write, P: NbtEncode {
-        let item_len = packet.encoded_size();
-        self.outgoing.reserve(item_len + (VarNum.encoded_size(&(item_len as i32))));
-        VarNum.encode(&(item_len as i32), &mut self.outgoing);
-        packet.encode(&mut self.outgoing);
-        out.write_all(&self.outgoing.take())
+        self.encoder.send(out, packet)
     }
 
     pub fn receive<P>(&mut self, mut input: impl Read) -> Result<P>
@@ -33,26 +59,23 @@ impl NbtCodec {
         self.incoming.reserve(len);
         self.incoming.resize(len, 0);
         input.read_exact(&mut self.incoming)?;
-        let message = P::decode(&mut self.incoming.split_to(len).freeze());
+        // The whole packet is consumed even if it fails to decode, so the
+        // stream stays in sync and the caller may keep reading
+        let message = P::decode(&mut self.incoming.split_to(len).freeze())?;
         Ok(message)
     }
 
     pub fn receive_timeout<P>(&mut self, input: &mut TcpStream, until: Instant) -> Result<Option<P>>
         where P: NbtDecode {
         loop {
-            match try_decode_length(&self.incoming) {
-                (Some(len), used) => {
-                    self.incoming.reserve(len + used);
-                    if self.incoming.len() >= len + used {
-                        self.incoming.split_to(used);
-                        let message = P::decode(&mut self.incoming.split_to(len).freeze());
-                        input.set_read_timeout(None)?;
-                        return Ok(Some(message))
-                    }
-                }
-                (None, used) => {
-                    self.incoming.reserve(used + 1);
+            if let (Some(len), used) = try_decode_length(&self.incoming).map_err(framing_error)? {
+                if self.incoming.len() >= len + used {
+                    self.incoming.split_to(used);
+                    input.set_read_timeout(None)?;
+                    let message = P::decode(&mut self.incoming.split_to(len).freeze())?;
+                    return Ok(Some(message))
                 }
+                self.incoming.reserve(len + used - self.incoming.len());
             }
 
             let now = Instant::now();
@@ -61,23 +84,43 @@ impl NbtCodec {
                 return Ok(None)
             }
             input.set_read_timeout(Some(until - now))?;
-            unsafe {
-                let read_result = input.read(self.incoming.bytes_mut());
-
-                match read_result {
-                    Err(e) => {
-                        if e.kind() != ErrorKind::WouldBlock {
-                            return Err(e)
-                        }
+            let mut buf = [0; READ_CHUNK];
+            match input.read(&mut buf) {
+                Err(e) => {
+                    if e.kind() != ErrorKind::WouldBlock {
+                        return Err(e.into())
                     }
-                    Ok(s) => self.incoming.advance_mut(s)
                 }
-            };
+                Ok(0) => return Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(s) => self.incoming.extend_from_slice(&buf[..s])
+            }
         }
     }
 }
 
-fn decode_length(mut input: impl Read) -> Result<usize> {
+// The sending half of a codec, for writing from another thread than the one
+// receiving
+#[derive(Clone, Default)]
+pub struct PacketEncoder {
+    outgoing: BytesMut
+}
+
+impl PacketEncoder {
+    pub fn new() -> Self {
+        PacketEncoder::default()
+    }
+
+    pub fn send<W, P>(&mut self, mut out: W, packet: P) -> io::Result<()>
+        where W: Write, P: NbtEncode {
+        let item_len = packet.encoded_size();
+        self.outgoing.reserve(item_len + (VarNum.encoded_size(&(item_len as i32))));
+        VarNum.encode(&(item_len as i32), &mut self.outgoing);
+        packet.encode(&mut self.outgoing);
+        out.write_all(&self.outgoing.take())
+    }
+}
+
+fn decode_length(mut input: impl Read) -> io::Result<usize> {
     let mut result = 0;
     let mut buf: [u8; 1] = [0; 1];
     let mut read = 0;
@@ -90,20 +133,89 @@ fn decode_length(mut input: impl Read) -> Result<usize> {
             return Ok(result);
         }
         read += 1;
+        if read == 5 {
+            return Err(framing_error(DecodeError::VarNumTooLong));
+        }
     }
 }
 
-fn try_decode_length(buf: &[u8]) -> (Option<usize>, usize) {
+// Nothing after a broken length can be framed, so the connection is as good
+// as lost
+pub(crate) fn framing_error(err: DecodeError) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, err)
+}
+
+// The length and how many bytes it took, or no length yet if it is incomplete
+pub(crate) fn try_decode_length(buf: &[u8]) -> DecodeResult<(Option<usize>, usize)> {
     let mut result = 0;
     let mut read = 0;
     for byte in buf {
         result = result | ((*byte as usize & 0x7F) << (read * 7));
 
         if byte & 0x80 == 0 {
-            return (Some(result), read + 1);
+            return Ok((Some(result), read + 1));
         }
         read += 1;
+        if read == 5 {
+            return Err(DecodeError::VarNumTooLong);
+        }
     }
 
-    (None, read)
+    Ok((None, read))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    #[test]
+    fn receive_timeout_waits_for_whole_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut server = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut client, _) = listener.accept().unwrap();
+        let mut codec = NbtCodec::new();
+
+        server.write_all(&[0x01]).unwrap();
+        let soon = Instant::now() + Duration::from_millis(50);
+        assert_eq!(codec.receive_timeout::<u8>(&mut client, soon).unwrap(), None);
+        server.write_all(&[0x2A]).unwrap();
+        let later = Instant::now() + Duration::from_secs(5);
+        assert_eq!(codec.receive_timeout::<u8>(&mut client, later).unwrap(), Some(0x2A));
+
+        // Longer than a single read
+        let mut frame = vec![0xA0, 0x9C, 0x01, 0x07];
+        frame.resize(3 + 20_000, 0);
+        frame.extend_from_slice(&[0x01, 0x08]);
+        server.write_all(&frame).unwrap();
+        assert_eq!(codec.receive_timeout::<u8>(&mut client, later).unwrap(), Some(0x07));
+        assert_eq!(codec.receive_timeout::<u8>(&mut client, later).unwrap(), Some(0x08));
+    }
+
+    #[test]
+    fn lengths_up_to_five_bytes() {
+        assert_eq!(decode_length(&[0x00][..]).unwrap(), 0);
+        assert_eq!(decode_length(&[0xFF, 0x01][..]).unwrap(), 255);
+        assert_eq!(decode_length(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07][..]).unwrap(), 0x7FFF_FFFF);
+        assert_eq!(try_decode_length(&[0xFF, 0x01, 0x42]).unwrap(), (Some(255), 2));
+        assert_eq!(try_decode_length(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07]).unwrap(), (Some(0x7FFF_FFFF), 5));
+        assert_eq!(try_decode_length(&[0xFF, 0xFF]).unwrap(), (None, 2));
+        assert_eq!(try_decode_length(&[0xFF, 0xFF, 0xFF, 0xFF]).unwrap(), (None, 4));
+    }
+
+    #[test]
+    fn lengths_longer_than_five_bytes() {
+        let overlong = [0xFF, 0xFF, 0xFF, 0xFF, 0x80];
+        let err = decode_length(&overlong[..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        match err.get_ref().and_then(|err| err.downcast_ref()) {
+            Some(DecodeError::VarNumTooLong) => (),
+            other => panic!("expected VarNumTooLong, got {:?}", other)
+        }
+        match try_decode_length(&overlong) {
+            Err(DecodeError::VarNumTooLong) => (),
+            other => panic!("expected VarNumTooLong, got {:?}", other)
+        }
+    }
 }
\ No newline at end of file
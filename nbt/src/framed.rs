@@ -0,0 +1,88 @@
+use bytes::{Bytes, BytesMut};
+use bytes05::Buf;
+use crate::{NbtDecode, NbtEncode, NbtEncoder, VarNum};
+use crate::codec::{framing_error, try_decode_length, CodecError};
+use std::marker::PhantomData;
+use tokio_util::codec::{Decoder, Encoder};
+
+// Packet framing for tokio_util's Framed. Decodes packets of type `P` and
+// encodes anything NbtEncode, switch `P` with Framed::into_parts when the
+// connection changes state.
+pub struct PacketCodec<P> {
+    _packet: PhantomData<fn() -> P>
+}
+
+impl <P> PacketCodec<P> {
+    pub fn new() -> Self {
+        PacketCodec {
+            _packet: PhantomData
+        }
+    }
+}
+
+impl <P> Default for PacketCodec<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl <P: NbtDecode> Decoder for PacketCodec<P> {
+    type Item = P;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut bytes05::BytesMut) -> Result<Option<P>, CodecError> {
+        match try_decode_length(&src[..]).map_err(framing_error)? {
+            (Some(len), used) => {
+                if src.len() < len + used {
+                    src.reserve(len + used - src.len());
+                    return Ok(None);
+                }
+                src.advance(used);
+                // nbt is still on bytes 0.4, so the frame gets copied over
+                let frame = src.split_to(len);
+                let message = P::decode(&mut Bytes::from(&frame[..]))?;
+                Ok(Some(message))
+            }
+            (None, _) => Ok(None)
+        }
+    }
+}
+
+impl <P, T: NbtEncode> Encoder<T> for PacketCodec<P> {
+    type Error = CodecError;
+
+    fn encode(&mut self, packet: T, dst: &mut bytes05::BytesMut) -> Result<(), CodecError> {
+        let item_len = packet.encoded_size();
+        let mut buf = BytesMut::with_capacity(item_len + VarNum.encoded_size(&(item_len as i32)));
+        VarNum.encode(&(item_len as i32), &mut buf);
+        packet.encode(&mut buf);
+        dst.extend_from_slice(&buf);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn waits_for_whole_frames() {
+        let mut codec = PacketCodec::<u8>::new();
+        let mut src = bytes05::BytesMut::from(&[0x01][..]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        src.extend_from_slice(&[0x2A, 0x01]);
+        assert_eq!(codec.decode(&mut src).unwrap(), Some(0x2A));
+        assert_eq!(&src[..], [0x01]);
+    }
+
+    #[test]
+    fn overlong_length_ends_the_connection() {
+        let mut codec = PacketCodec::<u8>::new();
+        let mut src = bytes05::BytesMut::from(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x2A][..]);
+        match codec.decode(&mut src) {
+            Err(CodecError::Io(ref err)) if err.kind() == ErrorKind::InvalidData => (),
+            other => panic!("expected an InvalidData error, got {:?}", other.map(|_| ()))
+        }
+    }
+}
@@ -1,4 +1,8 @@
+#[macro_use] extern crate quick_error;
+
 pub mod codec;
+#[cfg(feature = "tokio")]
+pub mod framed;
 
 pub use codec::NbtCodec;
 
@@ -13,8 +17,38 @@ use std::str::from_utf8;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
-pub trait NbtDecode {
-    fn decode(buf: &mut Bytes) -> Self;
+quick_error! {
+    #[derive(Debug)]
+    pub enum DecodeError {
+        UnexpectedEnd(needed: usize, remaining: usize) {
+            description("unexpected end of packet")
+            display("unexpected end of packet: needed {} bytes, {} remaining", needed, remaining)
+        }
+        VarNumTooLong {
+            description("varint is longer than 5 bytes")
+        }
+        InvalidUtf8(err: std::str::Utf8Error) {
+            description("invalid utf-8 string")
+            display("invalid utf-8 string: {}", err)
+            cause(err)
+            from()
+        }
+        InvalidJson(err: json::Error) {
+            description("invalid json")
+            display("invalid json: {}", err)
+            from()
+        }
+        InvalidValue(ty: &'static str, value: i64) {
+            description("invalid value")
+            display("invalid {} {}", ty, value)
+        }
+    }
+}
+
+pub type DecodeResult<T> = Result<T, DecodeError>;
+
+pub trait NbtDecode: Sized {
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self>;
 }
 
 pub trait NbtEncode {
@@ -23,7 +57,7 @@ pub trait NbtEncode {
 }
 
 pub trait NbtDecoder<T> {
-    fn decode(&self, buf: &mut Bytes) -> T;
+    fn decode(&self, buf: &mut Bytes) -> DecodeResult<T>;
 }
 
 pub trait NbtEncoder<T> {
@@ -35,16 +69,18 @@ pub trait NbtEncoder<T> {
 pub struct VarNum;
 
 impl NbtDecoder<i32> for VarNum {
-    fn decode(&self, buf: &mut Bytes) -> i32 {
+    fn decode(&self, buf: &mut Bytes) -> DecodeResult<i32> {
         let mut result = 0;
         let mut read = 0;
         loop {
-            let byte = buf[0];
-            buf.advance(1);
+            if read == 5 {
+                return Err(DecodeError::VarNumTooLong);
+            }
+            let byte = take(buf, 1)?[0];
             result = result | ((byte as i32 & 0x7F) << (read * 7));
 
             if byte & 0x80 == 0 {
-                return result;
+                return Ok(result);
             }
             read += 1;
         }
@@ -110,11 +146,12 @@ impl Display for NbtString {
 }
 
 impl NbtDecode for NbtString {
-    fn decode(buf: &mut Bytes) -> Self {
-        let len = VarNum.decode(buf);
-        NbtString {
-            bytes: buf.split_to(len as usize)
-        }
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        let bytes = Bytes::decode(buf)?;
+        from_utf8(&bytes[..])?;
+        Ok(NbtString {
+            bytes
+        })
     }
 }
 
@@ -144,8 +181,8 @@ impl NbtEncode for str {
 }
 
 impl NbtDecode for bool {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(1).into_buf().get_u8() > 0
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(u8::decode(buf)? > 0)
     }
 }
 
@@ -160,8 +197,8 @@ impl NbtEncode for bool {
 }
 
 impl NbtDecode for u8 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(1).into_buf().get_u8()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 1)?.into_buf().get_u8())
     }
 }
 
@@ -176,8 +213,8 @@ impl NbtEncode for u8 {
 }
 
 impl NbtDecode for u16 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(2).into_buf().get_u16_be()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 2)?.into_buf().get_u16_be())
     }
 }
 
@@ -192,8 +229,8 @@ impl NbtEncode for u16 {
 }
 
 impl NbtDecode for i16 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(2).into_buf().get_i16_be()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 2)?.into_buf().get_i16_be())
     }
 }
 
@@ -208,8 +245,8 @@ impl NbtEncode for i16 {
 }
 
 impl NbtDecode for i32 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(4).into_buf().get_i32_be()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 4)?.into_buf().get_i32_be())
     }
 }
 
@@ -224,8 +261,8 @@ impl NbtEncode for i32 {
 }
 
 impl NbtDecode for i64 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(8).into_buf().get_i64_be()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 8)?.into_buf().get_i64_be())
     }
 }
 
@@ -240,8 +277,8 @@ impl NbtEncode for i64 {
 }
 
 impl NbtDecode for u64 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(8).into_buf().get_u64_be()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 8)?.into_buf().get_u64_be())
     }
 }
 
@@ -256,8 +293,8 @@ impl NbtEncode for u64 {
 }
 
 impl NbtDecode for f32 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(4).into_buf().get_f32_be()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 4)?.into_buf().get_f32_be())
     }
 }
 
@@ -272,8 +309,8 @@ impl NbtEncode for f32 {
 }
 
 impl NbtDecode for f64 {
-    fn decode(buf: &mut Bytes) -> Self {
-        buf.split_to(8).into_buf().get_f64_be()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        Ok(take(buf, 8)?.into_buf().get_f64_be())
     }
 }
 
@@ -298,13 +335,14 @@ impl <T: NbtEncode> NbtEncode for Rc<T> {
 }
 
 impl <T: NbtDecode> NbtDecode for Vec<T> {
-    fn decode(buf: &mut Bytes) -> Self {
-        let len = VarNum.decode(buf);
-        let mut res = Vec::with_capacity(len as usize);
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        let len = length(buf)?;
+        // Every element takes at least a byte, don't trust the length any further
+        let mut res = Vec::with_capacity(len.min(buf.len()));
         for _ in 0..len {
-            res.push(T::decode(buf));
+            res.push(T::decode(buf)?);
         }
-        res
+        Ok(res)
     }
 }
 
@@ -324,35 +362,35 @@ impl <T: NbtEncode> NbtEncode for Vec<T> {
 }
 
 impl NbtDecode for Bytes {
-    fn decode(buf: &mut Bytes) -> Self {
-        let len = VarNum.decode(buf);
-        buf.split_to(len as usize)
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        let len = length(buf)?;
+        take(buf, len)
     }
 }
 
 impl NbtDecode for JsonValue {
-    fn decode(buf: &mut Bytes) -> Self {
-        let s = NbtString::decode(buf);
-        json::parse(s.as_ref()).unwrap()
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        let s = NbtString::decode(buf)?;
+        Ok(json::parse(s.as_ref())?)
     }
 }
 
 impl <T: NbtDecode> NbtDecode for Option<T> {
-    fn decode(buf: &mut Bytes) -> Self {
-        let exists = bool::decode(buf);
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        let exists = bool::decode(buf)?;
         if exists {
-            Some(T::decode(buf))
+            Ok(Some(T::decode(buf)?))
         } else {
-            None
+            Ok(None)
         }
     }
 }
 
 impl NbtDecode for Uuid {
-    fn decode(buf: &mut Bytes) -> Self {
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
         let mut bytes = [0u8; 16];
-        buf.split_to(16).into_buf().copy_to_slice(&mut bytes);
-        Uuid::from_bytes(bytes)
+        take(buf, 16)?.into_buf().copy_to_slice(&mut bytes);
+        Ok(Uuid::from_bytes(bytes))
     }
 }
 
@@ -364,4 +402,20 @@ impl <T: NbtEncode> NbtEncode for &T {
     fn encode<B: BufMut>(&self, buf: &mut B) {
         (*self).encode(buf)
     }
+}
+
+// Splits off the next `len` bytes, failing instead of panicking on short packets
+pub fn take(buf: &mut Bytes, len: usize) -> DecodeResult<Bytes> {
+    if buf.len() < len {
+        return Err(DecodeError::UnexpectedEnd(len, buf.len()));
+    }
+    Ok(buf.split_to(len))
+}
+
+fn length(buf: &mut Bytes) -> DecodeResult<usize> {
+    let len = VarNum.decode(buf)?;
+    if len < 0 {
+        return Err(DecodeError::InvalidValue("length", len as i64));
+    }
+    Ok(len as usize)
 }
\ No newline at end of file
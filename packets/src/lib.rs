@@ -30,6 +30,10 @@ pub enum ClientLoginPacket {
 
 #[derive(Debug, NbtDecode)]
 pub enum ServerLoginPacket {
+    #[nbt(ordinal = "0")]
+    Disconnect {
+        reason: JsonValue
+    },
     #[nbt(ordinal = "2")]
     LoginSuccess {
         uuid: NbtString,
@@ -73,6 +77,21 @@ pub enum ClientPacket {
         pitch: f32,
         on_ground: bool
     },
+    #[nbt(ordinal = "20")]
+    PlayerDigging {
+        #[nbt(codec = "varnum")] status: i32,
+        location: u64,
+        face: u8
+    },
+    #[nbt(ordinal = "31")]
+    PlayerBlockPlacement {
+        location: u64,
+        #[nbt(codec = "varnum")] face: i32,
+        #[nbt(codec = "varnum")] hand: i32,
+        cursor_x: f32,
+        cursor_y: f32,
+        cursor_z: f32
+    },
 }
 
 #[derive(Debug, NbtDecode)]
@@ -211,7 +230,7 @@ pub enum ServerPacket {
     },
     #[nbt(ordinal = "26")]
     Disconnect {
-        // TODO: Rest
+        reason: JsonValue
     },
     #[nbt(ordinal = "27")]
     EntityStatus {
@@ -280,8 +299,13 @@ pub enum ServerPacket {
     },
     #[nbt(ordinal = "39")]
     EntityLookAndRelativeMove {
-        #[nbt(codec = "varnum")] entity_id: i32
-        // TODO: Rest
+        #[nbt(codec = "varnum")] entity_id: i32,
+        delta_x: i16,
+        delta_y: i16,
+        delta_z: i16,
+        yaw: Angle,
+        pitch: Angle,
+        on_ground: bool
     },
     #[nbt(ordinal = "40")]
     EntityLook {
@@ -310,7 +334,7 @@ pub enum ServerPacket {
     },
     #[nbt(ordinal = "45")]
     CombatEvent {
-        // TODO: Rest
+        event: CombatEventPacket
     },
     #[nbt(ordinal = "46")]
     PlayerList {
@@ -339,7 +363,7 @@ pub enum ServerPacket {
     },
     #[nbt(ordinal = "50")]
     DestroyEntities {
-        // TODO: Rest
+        entities: Vec<DestroyedEntity>
     },
     #[nbt(ordinal = "51")]
     RemoveEntityEffect {
@@ -351,7 +375,10 @@ pub enum ServerPacket {
     },
     #[nbt(ordinal = "53")]
     Respawn {
-        // TODO: Rest
+        dimension: DimensionId,
+        difficulty: Difficulty,
+        game_mode: FullGameMode,
+        level_type: NbtString
     },
     #[nbt(ordinal = "54")]
     EntityHeadLook {
@@ -452,7 +479,13 @@ pub enum ServerPacket {
     },
     #[nbt(ordinal = "76")]
     EntityTeleport {
-        // TODO: Rest
+        #[nbt(codec = "varnum")] entity_id: i32,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: Angle,
+        pitch: Angle,
+        on_ground: bool
     },
     #[nbt(ordinal = "77")]
     Advancements {
@@ -475,6 +508,11 @@ pub struct MultiBlockChangeRecord {
     #[nbt(codec = "varnum")] pub block_state: i32
 }
 
+#[derive(Debug, NbtDecode)]
+pub struct DestroyedEntity {
+    #[nbt(codec = "varnum")] pub entity_id: i32
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum GameMode {
     Survival,
@@ -490,25 +528,25 @@ pub struct FullGameMode {
 }
 
 impl NbtDecode for FullGameMode {
-    fn decode(buf: &mut Bytes) -> Self {
-        let code = u8::decode(buf);
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        let code = u8::decode(buf)?;
         let mode = match code & 0x0F {
             0 => GameMode::Survival,
             1 => GameMode::Creative,
             2 => GameMode::Adventure,
             3 => GameMode::Spectator,
-            _ => panic!("Unexpected code {}", code)
+            _ => return Err(DecodeError::InvalidValue("game mode", code as i64))
         };
         let hardcore = code & 0xF0 > 0;
 
-        FullGameMode {
+        Ok(FullGameMode {
             mode,
             hardcore
-        }
+        })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DimensionId {
     Nether,
     Overworld,
@@ -516,12 +554,12 @@ pub enum DimensionId {
 }
 
 impl NbtDecode for DimensionId {
-    fn decode(buf: &mut Bytes) -> Self {
-        match i32::decode(buf) {
-            -1 => DimensionId::Nether,
-            0 => DimensionId::Overworld,
-            1 => DimensionId::End,
-            d => panic!("Unexpected dimension {}", d)
+    fn decode(buf: &mut Bytes) -> DecodeResult<Self> {
+        match i32::decode(buf)? {
+            -1 => Ok(DimensionId::Nether),
+            0 => Ok(DimensionId::Overworld),
+            1 => Ok(DimensionId::End),
+            d => Err(DecodeError::InvalidValue("dimension", d as i64))
         }
     }
 }
@@ -558,6 +596,24 @@ pub enum PlayerListPacket {
     },
 }
 
+#[derive(Debug, NbtDecode)]
+pub enum CombatEventPacket {
+    #[nbt(ordinal = "0")]
+    EnterCombat {
+    },
+    #[nbt(ordinal = "1")]
+    EndCombat {
+        #[nbt(codec = "varnum")] duration: i32,
+        entity_id: i32
+    },
+    #[nbt(ordinal = "2")]
+    EntityDead {
+        #[nbt(codec = "varnum")] player_id: i32,
+        entity_id: i32,
+        message: JsonValue
+    }
+}
+
 #[derive(Debug, NbtDecode)]
 pub struct AddPlayer {
     pub uuid: Uuid,